@@ -0,0 +1,572 @@
+/// Distance-based bond inference using cell-list spatial search.
+
+use std::collections::HashSet;
+
+/// Covalent radii in Angstroms, indexed by atomic number.
+fn covalent_radius(atomic_num: u8) -> f32 {
+    match atomic_num {
+        1 => 0.31,   // H
+        5 => 0.84,   // B
+        6 => 0.76,   // C
+        7 => 0.71,   // N
+        8 => 0.66,   // O
+        9 => 0.57,   // F
+        11 => 1.66,  // Na
+        12 => 1.41,  // Mg
+        14 => 1.11,  // Si
+        15 => 1.07,  // P
+        16 => 1.05,  // S
+        17 => 1.02,  // Cl
+        19 => 2.03,  // K
+        20 => 1.76,  // Ca
+        25 => 1.39,  // Mn
+        26 => 1.32,  // Fe
+        27 => 1.26,  // Co
+        28 => 1.24,  // Ni
+        29 => 1.32,  // Cu
+        30 => 1.22,  // Zn
+        34 => 1.20,  // Se
+        35 => 1.20,  // Br
+        53 => 1.39,  // I
+        _ => 0.77,
+    }
+}
+
+/// Van der Waals radii in Angstroms, indexed by atomic number.
+/// Matches the constants in src/core/constants.ts.
+pub fn vdw_radius(atomic_num: u8) -> f32 {
+    match atomic_num {
+        1 => 1.20,   // H
+        6 => 1.70,   // C
+        7 => 1.55,   // N
+        8 => 1.52,   // O
+        9 => 1.47,   // F
+        11 => 2.27,  // Na
+        12 => 1.73,  // Mg
+        15 => 1.80,  // P
+        16 => 1.80,  // S
+        17 => 1.75,  // Cl
+        19 => 2.75,  // K
+        20 => 2.31,  // Ca
+        26 => 2.04,  // Fe
+        29 => 1.40,  // Cu
+        30 => 1.39,  // Zn
+        _ => 1.50,
+    }
+}
+
+const BOND_TOLERANCE: f32 = 1.3;
+const MIN_BOND_DIST: f32 = 0.4;
+const VDW_BOND_FACTOR: f32 = 0.6;
+
+/// Generic cell-list spatial scan that iterates over all nearby atom pairs
+/// and calls `check_pair(i, j)` for each. The closure returns `Some((a, b))`
+/// if the pair should be recorded as a bond.
+fn cell_list_scan<F>(
+    positions: &[f32],
+    n_atoms: usize,
+    cell_size: f32,
+    mut check_pair: F,
+) -> Vec<(u32, u32)>
+where
+    F: FnMut(usize, usize) -> Option<(u32, u32)>,
+{
+    if n_atoms == 0 {
+        return Vec::new();
+    }
+
+    // Bounding box
+    let (mut min_x, mut min_y, mut min_z) = (f32::MAX, f32::MAX, f32::MAX);
+    let (mut max_x, mut max_y, mut max_z) = (f32::MIN, f32::MIN, f32::MIN);
+
+    for i in 0..n_atoms {
+        let (x, y, z) = (positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]);
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        min_z = min_z.min(z);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+        max_z = max_z.max(z);
+    }
+
+    let nx = ((max_x - min_x) / cell_size).ceil().max(1.0) as usize;
+    let ny = ((max_y - min_y) / cell_size).ceil().max(1.0) as usize;
+    let nz = ((max_z - min_z) / cell_size).ceil().max(1.0) as usize;
+
+    // Build cell lists
+    let mut cells: Vec<Vec<usize>> = vec![Vec::new(); nx * ny * nz];
+
+    for i in 0..n_atoms {
+        let cx = (((positions[i * 3] - min_x) / cell_size) as usize).min(nx - 1);
+        let cy = (((positions[i * 3 + 1] - min_y) / cell_size) as usize).min(ny - 1);
+        let cz = (((positions[i * 3 + 2] - min_z) / cell_size) as usize).min(nz - 1);
+        cells[cx * ny * nz + cy * nz + cz].push(i);
+    }
+
+    let mut bonds = Vec::new();
+
+    // 13 neighbor offsets (half-shell to avoid double-counting)
+    let offsets: [(isize, isize, isize); 13] = [
+        (0, 0, 1),
+        (0, 1, -1),
+        (0, 1, 0),
+        (0, 1, 1),
+        (1, -1, -1),
+        (1, -1, 0),
+        (1, -1, 1),
+        (1, 0, -1),
+        (1, 0, 0),
+        (1, 0, 1),
+        (1, 1, -1),
+        (1, 1, 0),
+        (1, 1, 1),
+    ];
+
+    for cx in 0..nx {
+        for cy in 0..ny {
+            for cz in 0..nz {
+                let cell_idx = cx * ny * nz + cy * nz + cz;
+                let cell = &cells[cell_idx];
+
+                // Pairs within the same cell
+                for ii in 0..cell.len() {
+                    let i = cell[ii];
+
+                    for jj in (ii + 1)..cell.len() {
+                        let j = cell[jj];
+                        if let Some(bond) = check_pair(i, j) {
+                            bonds.push(bond);
+                        }
+                    }
+
+                    // Pairs with neighboring cells (half-shell)
+                    for &(dx, dy, dz) in &offsets {
+                        let ncx = cx as isize + dx;
+                        let ncy = cy as isize + dy;
+                        let ncz = cz as isize + dz;
+                        if ncx < 0
+                            || ncy < 0
+                            || ncz < 0
+                            || ncx >= nx as isize
+                            || ncy >= ny as isize
+                            || ncz >= nz as isize
+                        {
+                            continue;
+                        }
+                        let neighbor_idx =
+                            ncx as usize * ny * nz + ncy as usize * nz + ncz as usize;
+                        for &j in &cells[neighbor_idx] {
+                            if let Some(bond) = check_pair(i, j) {
+                                bonds.push(bond);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    bonds
+}
+
+/// Invert a row-major 3x3 matrix, or `None` if it is singular.
+pub(crate) fn invert3(m: &[f32; 9]) -> Option<[f32; 9]> {
+    let (a, b, c, d, e, f, g, h, i) = (m[0], m[1], m[2], m[3], m[4], m[5], m[6], m[7], m[8]);
+    let det = a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g);
+    if det.abs() < 1e-8 {
+        return None;
+    }
+    let inv_det = 1.0 / det;
+    Some([
+        (e * i - f * h) * inv_det, (c * h - b * i) * inv_det, (b * f - c * e) * inv_det,
+        (f * g - d * i) * inv_det, (a * i - c * g) * inv_det, (c * d - a * f) * inv_det,
+        (d * h - e * g) * inv_det, (b * g - a * h) * inv_det, (a * e - b * d) * inv_det,
+    ])
+}
+
+/// Apply the minimum-image convention to a Cartesian displacement `(dx, dy, dz)`
+/// given a periodic box. Orthorhombic boxes (no off-diagonal terms) take the
+/// fast per-axis path; general triclinic boxes wrap in fractional coordinates.
+fn minimum_image(dx: f32, dy: f32, dz: f32, box_matrix: &[f32; 9]) -> (f32, f32, f32) {
+    let is_orthorhombic = box_matrix[1] == 0.0
+        && box_matrix[2] == 0.0
+        && box_matrix[3] == 0.0
+        && box_matrix[5] == 0.0
+        && box_matrix[6] == 0.0
+        && box_matrix[7] == 0.0;
+
+    if is_orthorhombic {
+        let (lx, ly, lz) = (box_matrix[0], box_matrix[4], box_matrix[8]);
+        let wx = if lx > 0.0 { dx - lx * (dx / lx).round() } else { dx };
+        let wy = if ly > 0.0 { dy - ly * (dy / ly).round() } else { dy };
+        let wz = if lz > 0.0 { dz - lz * (dz / lz).round() } else { dz };
+        return (wx, wy, wz);
+    }
+
+    let Some(inv) = invert3(box_matrix) else {
+        return (dx, dy, dz);
+    };
+
+    // Fractional coordinates: f = d * B^-1 (row-vector convention, box rows
+    // are the lattice vectors).
+    let f0 = dx * inv[0] + dy * inv[3] + dz * inv[6];
+    let f1 = dx * inv[1] + dy * inv[4] + dz * inv[7];
+    let f2 = dx * inv[2] + dy * inv[5] + dz * inv[8];
+
+    let r0 = f0.round();
+    let r1 = f1.round();
+    let r2 = f2.round();
+
+    let cx = r0 * box_matrix[0] + r1 * box_matrix[3] + r2 * box_matrix[6];
+    let cy = r0 * box_matrix[1] + r1 * box_matrix[4] + r2 * box_matrix[7];
+    let cz = r0 * box_matrix[2] + r1 * box_matrix[5] + r2 * box_matrix[8];
+
+    (dx - cx, dy - cy, dz - cz)
+}
+
+/// Variant of [`cell_list_scan`] that wraps neighbor cell indices modulo the
+/// grid dimensions and hands `check_pair` the minimum-image displacement, so
+/// atoms bonded across a periodic boundary are scanned as actual neighbors.
+fn cell_list_scan_pbc<F>(
+    positions: &[f32],
+    n_atoms: usize,
+    cell_size: f32,
+    box_matrix: &[f32; 9],
+    mut check_pair: F,
+) -> Vec<(u32, u32)>
+where
+    F: FnMut(usize, usize, f32, f32, f32) -> Option<(u32, u32)>,
+{
+    if n_atoms == 0 {
+        return Vec::new();
+    }
+
+    let (mut min_x, mut min_y, mut min_z) = (f32::MAX, f32::MAX, f32::MAX);
+    let (mut max_x, mut max_y, mut max_z) = (f32::MIN, f32::MIN, f32::MIN);
+
+    for i in 0..n_atoms {
+        let (x, y, z) = (positions[i * 3], positions[i * 3 + 1], positions[i * 3 + 2]);
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        min_z = min_z.min(z);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+        max_z = max_z.max(z);
+    }
+
+    let nx = ((max_x - min_x) / cell_size).ceil().max(1.0) as usize;
+    let ny = ((max_y - min_y) / cell_size).ceil().max(1.0) as usize;
+    let nz = ((max_z - min_z) / cell_size).ceil().max(1.0) as usize;
+
+    let mut cells: Vec<Vec<usize>> = vec![Vec::new(); nx * ny * nz];
+
+    for i in 0..n_atoms {
+        let cx = (((positions[i * 3] - min_x) / cell_size) as usize).min(nx - 1);
+        let cy = (((positions[i * 3 + 1] - min_y) / cell_size) as usize).min(ny - 1);
+        let cz = (((positions[i * 3 + 2] - min_z) / cell_size) as usize).min(nz - 1);
+        cells[cx * ny * nz + cy * nz + cz].push(i);
+    }
+
+    let mut bonds = Vec::new();
+
+    let offsets: [(isize, isize, isize); 13] = [
+        (0, 0, 1),
+        (0, 1, -1),
+        (0, 1, 0),
+        (0, 1, 1),
+        (1, -1, -1),
+        (1, -1, 0),
+        (1, -1, 1),
+        (1, 0, -1),
+        (1, 0, 0),
+        (1, 0, 1),
+        (1, 1, -1),
+        (1, 1, 0),
+        (1, 1, 1),
+    ];
+
+    let wrap = |v: isize, n: usize| -> usize { v.rem_euclid(n as isize) as usize };
+
+    let check_dist = |i: usize, j: usize, check_pair: &mut F| {
+        let dx = positions[j * 3] - positions[i * 3];
+        let dy = positions[j * 3 + 1] - positions[i * 3 + 1];
+        let dz = positions[j * 3 + 2] - positions[i * 3 + 2];
+        let (dx, dy, dz) = minimum_image(dx, dy, dz, box_matrix);
+        check_pair(i, j, dx, dy, dz)
+    };
+
+    for cx in 0..nx {
+        for cy in 0..ny {
+            for cz in 0..nz {
+                let cell_idx = cx * ny * nz + cy * nz + cz;
+                let cell = &cells[cell_idx];
+
+                for ii in 0..cell.len() {
+                    let i = cell[ii];
+
+                    for jj in (ii + 1)..cell.len() {
+                        let j = cell[jj];
+                        if let Some(bond) = check_dist(i, j, &mut check_pair) {
+                            bonds.push(bond);
+                        }
+                    }
+
+                    // Pairs with neighboring cells, wrapping around the box
+                    // edges so periodic neighbors are scanned instead of
+                    // discarded.
+                    for &(dx, dy, dz) in &offsets {
+                        let ncx = wrap(cx as isize + dx, nx);
+                        let ncy = wrap(cy as isize + dy, ny);
+                        let ncz = wrap(cz as isize + dz, nz);
+                        let neighbor_idx = ncx * ny * nz + ncy * nz + ncz;
+                        for &j in &cells[neighbor_idx] {
+                            if let Some(bond) = check_dist(i, j, &mut check_pair) {
+                                bonds.push(bond);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    bonds
+}
+
+/// Infer bonds using a cell-list (spatial hashing) approach.
+pub fn infer_bonds(
+    positions: &[f32],
+    elements: &[u8],
+    n_atoms: usize,
+    existing_bonds: &HashSet<(u32, u32)>,
+) -> Vec<(u32, u32)> {
+    let cell_size: f32 = 2.5;
+
+    cell_list_scan(positions, n_atoms, cell_size, |i, j| {
+        let a = i.min(j) as u32;
+        let b = i.max(j) as u32;
+        if existing_bonds.contains(&(a, b)) {
+            return None;
+        }
+
+        let ri = covalent_radius(elements[i]);
+        let rj = covalent_radius(elements[j]);
+        let threshold = (ri + rj) * BOND_TOLERANCE;
+
+        let dx = positions[j * 3] - positions[i * 3];
+        let dy = positions[j * 3 + 1] - positions[i * 3 + 1];
+        let dz = positions[j * 3 + 2] - positions[i * 3 + 2];
+
+        let dist_sq = dx * dx + dy * dy + dz * dz;
+        if dist_sq > MIN_BOND_DIST * MIN_BOND_DIST && dist_sq <= threshold * threshold {
+            Some((a, b))
+        } else {
+            None
+        }
+    })
+}
+
+/// Infer bonds using VDW radii: bond if distance <= (vdw_i + vdw_j) * 0.6.
+pub fn infer_bonds_vdw(
+    positions: &[f32],
+    elements: &[u8],
+    n_atoms: usize,
+) -> Vec<(u32, u32)> {
+    let cell_size: f32 = 2.0;
+
+    cell_list_scan(positions, n_atoms, cell_size, |i, j| {
+        let ri = vdw_radius(elements[i]);
+        let rj = vdw_radius(elements[j]);
+        let threshold = (ri + rj) * VDW_BOND_FACTOR;
+
+        let dx = positions[j * 3] - positions[i * 3];
+        let dy = positions[j * 3 + 1] - positions[i * 3 + 1];
+        let dz = positions[j * 3 + 2] - positions[i * 3 + 2];
+
+        let dist_sq = dx * dx + dy * dy + dz * dz;
+        if dist_sq > MIN_BOND_DIST * MIN_BOND_DIST && dist_sq <= threshold * threshold {
+            let a = i.min(j) as u32;
+            let b = i.max(j) as u32;
+            Some((a, b))
+        } else {
+            None
+        }
+    })
+}
+
+/// Infer bonds applying the minimum-image convention against a periodic box,
+/// so atoms bonded across a periodic edge (common in GRO/XTC trajectory
+/// frames carrying a `box_matrix`) are found instead of missed.
+pub fn infer_bonds_pbc(
+    positions: &[f32],
+    elements: &[u8],
+    n_atoms: usize,
+    box_matrix: &[f32; 9],
+) -> Vec<(u32, u32)> {
+    let cell_size: f32 = 2.5;
+
+    let raw = cell_list_scan_pbc(positions, n_atoms, cell_size, box_matrix, |i, j, dx, dy, dz| {
+        let a = i.min(j) as u32;
+        let b = i.max(j) as u32;
+
+        let ri = covalent_radius(elements[i]);
+        let rj = covalent_radius(elements[j]);
+        let threshold = (ri + rj) * BOND_TOLERANCE;
+
+        let dist_sq = dx * dx + dy * dy + dz * dz;
+        if dist_sq > MIN_BOND_DIST * MIN_BOND_DIST && dist_sq <= threshold * threshold {
+            Some((a, b))
+        } else {
+            None
+        }
+    });
+
+    // Small boxes (grid dimension <= 2) can visit the same periodic pair
+    // through more than one neighbor offset; drop the resulting duplicates.
+    let mut seen: HashSet<(u32, u32)> = HashSet::new();
+    raw.into_iter().filter(|bond| seen.insert(*bond)).collect()
+}
+
+/// Typical target valence for a neutral atom, used by bond-order
+/// perception. Elements with a variable valence (S, P) use their lower,
+/// more common one; unlisted elements return 0 and are excluded from
+/// perception.
+fn base_valence(atomic_num: u8) -> u8 {
+    match atomic_num {
+        1 => 1,           // H
+        6 => 4,           // C
+        7 => 3,           // N
+        8 => 2,           // O
+        9 | 17 | 35 | 53 => 1, // halogens
+        15 => 3,          // P
+        16 => 2,          // S
+        _ => 0,
+    }
+}
+
+/// Target valence adjusted for formal charge, e.g. an ammonium nitrogen
+/// (N, +1) gets valence 4 instead of the neutral 3, and a carboxylate
+/// oxygen (O, -1) gets valence 1 instead of 2. Returns 0 (excluded from
+/// perception) for unlisted elements.
+fn target_valence(atomic_num: u8, formal_charge: i8) -> i32 {
+    let base = base_valence(atomic_num);
+    if base == 0 {
+        return 0;
+    }
+    (base as i32 + formal_charge as i32).max(0)
+}
+
+/// Perceive bond orders from geometry and valence.
+///
+/// For each bond, compute the ratio of the observed distance to the
+/// expected single-bond length (sum of covalent radii). Bonds substantially
+/// shorter than that reference are candidates for a multiple bond. Resolve
+/// orders by sorting candidates by how far below their single-bond
+/// reference they are and greedily promoting each to order 2 (or 3 for very
+/// short C≡C/C≡N/N≡N bonds) only while both endpoints still have unfilled
+/// valence, decrementing the remaining valence of both atoms after each
+/// assignment. Every other bond stays at order 1.
+pub fn perceive_bond_orders(
+    positions: &[f32],
+    elements: &[u8],
+    bonds: &[(u32, u32)],
+    formal_charges: &[i8],
+) -> Vec<u8> {
+    let mut orders = vec![1u8; bonds.len()];
+
+    let mut remaining: Vec<i32> = elements
+        .iter()
+        .zip(formal_charges.iter())
+        .map(|(&z, &q)| target_valence(z, q))
+        .collect();
+    for &(a, b) in bonds {
+        remaining[a as usize] -= 1;
+        remaining[b as usize] -= 1;
+    }
+
+    struct Candidate {
+        idx: usize,
+        ratio: f32,
+    }
+
+    const CANDIDATE_RATIO: f32 = 0.93;
+    const TRIPLE_RATIO: f32 = 0.78;
+
+    let mut candidates = Vec::new();
+    for (idx, &(a, b)) in bonds.iter().enumerate() {
+        let (za, zb) = (elements[a as usize], elements[b as usize]);
+        if base_valence(za) == 0 || base_valence(zb) == 0 {
+            continue;
+        }
+        let ref_len = covalent_radius(za) + covalent_radius(zb);
+        if ref_len <= 0.0 {
+            continue;
+        }
+
+        let (ai, bi) = (a as usize, b as usize);
+        let dx = positions[bi * 3] - positions[ai * 3];
+        let dy = positions[bi * 3 + 1] - positions[ai * 3 + 1];
+        let dz = positions[bi * 3 + 2] - positions[ai * 3 + 2];
+        let dist = (dx * dx + dy * dy + dz * dz).sqrt();
+
+        let ratio = dist / ref_len;
+        if ratio < CANDIDATE_RATIO {
+            candidates.push(Candidate { idx, ratio });
+        }
+    }
+
+    candidates.sort_by(|c1, c2| c1.ratio.partial_cmp(&c2.ratio).unwrap());
+
+    for candidate in candidates {
+        let (a, b) = bonds[candidate.idx];
+        let (ai, bi) = (a as usize, b as usize);
+        if remaining[ai] <= 0 || remaining[bi] <= 0 {
+            continue;
+        }
+
+        let (za, zb) = (elements[ai], elements[bi]);
+        let triple_eligible = matches!((za, zb), (6, 6) | (6, 7) | (7, 6) | (7, 7));
+        let target_order: i32 = if triple_eligible
+            && candidate.ratio < TRIPLE_RATIO
+            && remaining[ai] >= 2
+            && remaining[bi] >= 2
+        {
+            3
+        } else {
+            2
+        };
+
+        let delta = target_order - orders[candidate.idx] as i32;
+        if delta <= 0 || remaining[ai] < delta || remaining[bi] < delta {
+            continue;
+        }
+
+        orders[candidate.idx] = target_order as u8;
+        remaining[ai] -= delta;
+        remaining[bi] -= delta;
+    }
+
+    orders
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn infer_bonds_pbc_finds_bond_across_box_edge() {
+        // Two carbons 1.4 A apart across the box boundary along x: one at
+        // x=0.7, the other at x=9.3 in a 10x10x10 box (wrapped separation
+        // is 1.4 A, not the raw 8.6 A).
+        let positions: Vec<f32> = vec![0.7, 5.0, 5.0, 9.3, 5.0, 5.0];
+        let elements = vec![6u8, 6u8];
+        let box_matrix = [10.0, 0.0, 0.0, 0.0, 10.0, 0.0, 0.0, 0.0, 10.0];
+
+        let bonds = infer_bonds_pbc(&positions, &elements, 2, &box_matrix);
+        assert_eq!(bonds, vec![(0, 1)]);
+
+        let empty_bonds = HashSet::new();
+        let non_pbc_bonds = infer_bonds(&positions, &elements, 2, &empty_bonds);
+        assert!(non_pbc_bonds.is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+/// Connected-component fragment analysis over the bond graph.
+///
+/// Once `bonds` is final, atoms separate into connected components — one per
+/// molecule. This lets callers strip crystallographic depositions down to
+/// the molecule(s) of interest (e.g. a ligand) before rendering, dropping
+/// bundled counterions and waters.
+
+use std::collections::HashMap;
+
+use crate::parser::ParsedStructure;
+
+/// Union-find (disjoint-set) with path compression and union by rank.
+struct UnionFind {
+    parent: Vec<u32>,
+    rank: Vec<u8>,
+}
+
+impl UnionFind {
+    fn new(n: usize) -> Self {
+        UnionFind {
+            parent: (0..n as u32).collect(),
+            rank: vec![0; n],
+        }
+    }
+
+    fn find(&mut self, x: u32) -> u32 {
+        if self.parent[x as usize] != x {
+            self.parent[x as usize] = self.find(self.parent[x as usize]);
+        }
+        self.parent[x as usize]
+    }
+
+    fn union(&mut self, a: u32, b: u32) {
+        let (ra, rb) = (self.find(a), self.find(b));
+        if ra == rb {
+            return;
+        }
+        match self.rank[ra as usize].cmp(&self.rank[rb as usize]) {
+            std::cmp::Ordering::Less => self.parent[ra as usize] = rb,
+            std::cmp::Ordering::Greater => self.parent[rb as usize] = ra,
+            std::cmp::Ordering::Equal => {
+                self.parent[rb as usize] = ra;
+                self.rank[ra as usize] += 1;
+            }
+        }
+    }
+}
+
+/// Group atoms into connected components (molecules) via the final bond
+/// graph, using union-find. Returns one `Vec<u32>` of atom indices per
+/// fragment, each sorted ascending, ordered by each fragment's lowest atom
+/// index.
+pub fn fragments(structure: &ParsedStructure) -> Vec<Vec<u32>> {
+    let n = structure.n_atoms;
+    let mut uf = UnionFind::new(n);
+    for &(a, b) in &structure.bonds {
+        uf.union(a, b);
+    }
+
+    let mut groups: HashMap<u32, Vec<u32>> = HashMap::new();
+    for i in 0..n as u32 {
+        let root = uf.find(i);
+        groups.entry(root).or_default().push(i);
+    }
+
+    let mut result: Vec<Vec<u32>> = groups.into_values().collect();
+    result.sort_by_key(|frag| frag[0]);
+    result
+}
+
+/// Options controlling [`strip_salts`].
+pub struct StripOptions {
+    /// Drop fragments matching the built-in counterion/solvent table.
+    pub remove_known_salts: bool,
+    /// Keep only the N largest remaining fragments (by atom count).
+    /// `None` keeps all of them.
+    pub keep_largest: Option<usize>,
+}
+
+impl Default for StripOptions {
+    fn default() -> Self {
+        StripOptions {
+            remove_known_salts: true,
+            keep_largest: None,
+        }
+    }
+}
+
+/// Built-in table of common crystallographic counterions and solvent
+/// molecules, recognized by sorted element composition (atomic numbers)
+/// regardless of connectivity or coordinates.
+const KNOWN_SALTS_AND_SOLVENTS: &[&[u8]] = &[
+    &[11],             // Na+
+    &[17],             // Cl-
+    &[19],             // K+
+    &[12],             // Mg2+
+    &[20],             // Ca2+
+    &[30],             // Zn2+
+    &[1, 1, 8],        // water
+    &[8, 8, 8, 8, 16], // sulfate (SO4)
+    &[8, 8, 8, 8, 15], // phosphate (PO4)
+    &[1, 1, 1, 1, 7],  // ammonium (NH4+)
+];
+
+fn is_known_salt_or_solvent(elements: &[u8]) -> bool {
+    let mut sorted = elements.to_vec();
+    sorted.sort_unstable();
+    KNOWN_SALTS_AND_SOLVENTS
+        .iter()
+        .any(|pattern| *pattern == sorted.as_slice())
+}
+
+/// Remove unwanted fragments from `structure` in place: known
+/// counterions/solvent (see [`is_known_salt_or_solvent`]) when
+/// `opts.remove_known_salts` is set, and/or all but the `opts.keep_largest`
+/// biggest remaining fragments. Rebuilds `positions`, `elements`,
+/// `formal_charges`, `bonds` (with remapped indices), `n_atoms`,
+/// `n_file_bonds`, `velocities`, and every entry of `frame_positions`
+/// consistently.
+pub fn strip_salts(structure: &mut ParsedStructure, opts: &StripOptions) {
+    let mut frags = fragments(structure);
+
+    if opts.remove_known_salts {
+        frags.retain(|frag| {
+            let frag_elements: Vec<u8> = frag.iter().map(|&i| structure.elements[i as usize]).collect();
+            !is_known_salt_or_solvent(&frag_elements)
+        });
+    }
+
+    if let Some(keep) = opts.keep_largest {
+        frags.sort_by_key(|f| std::cmp::Reverse(f.len()));
+        frags.truncate(keep);
+        frags.sort_by_key(|f| f[0]);
+    }
+
+    let mut keep_atoms: Vec<u32> = frags.into_iter().flatten().collect();
+    keep_atoms.sort_unstable();
+
+    rebuild(structure, &keep_atoms);
+}
+
+/// Rebuild every per-atom and per-bond field of `structure` to contain only
+/// `keep_atoms`, with indices remapped to be contiguous starting at 0.
+fn rebuild(structure: &mut ParsedStructure, keep_atoms: &[u32]) {
+    let mut old_to_new: HashMap<u32, u32> = HashMap::with_capacity(keep_atoms.len());
+    let mut positions = Vec::with_capacity(keep_atoms.len() * 3);
+    let mut elements = Vec::with_capacity(keep_atoms.len());
+    let mut formal_charges = Vec::with_capacity(keep_atoms.len());
+
+    for (new_idx, &old_idx) in keep_atoms.iter().enumerate() {
+        old_to_new.insert(old_idx, new_idx as u32);
+        let old = old_idx as usize;
+        positions.push(structure.positions[old * 3]);
+        positions.push(structure.positions[old * 3 + 1]);
+        positions.push(structure.positions[old * 3 + 2]);
+        elements.push(structure.elements[old]);
+        formal_charges.push(structure.formal_charges[old]);
+    }
+
+    // Remap a bond slice, keeping any parallel per-bond order value (if
+    // present) aligned by index with the surviving bonds.
+    let remap_bonds = |bonds: &[(u32, u32)], orders: Option<&[u8]>| -> (Vec<(u32, u32)>, Option<Vec<u8>>) {
+        let mut new_bonds = Vec::new();
+        let mut new_orders = orders.map(|_| Vec::new());
+        for (i, &(a, b)) in bonds.iter().enumerate() {
+            let (Some(&na), Some(&nb)) = (old_to_new.get(&a), old_to_new.get(&b)) else {
+                continue;
+            };
+            new_bonds.push((na.min(nb), na.max(nb)));
+            if let (Some(orders), Some(new_orders)) = (orders, new_orders.as_mut()) {
+                new_orders.push(orders[i]);
+            }
+        }
+        (new_bonds, new_orders)
+    };
+
+    let n_file_bonds_old = structure.n_file_bonds;
+    let file_orders = structure
+        .bond_orders
+        .as_deref()
+        .map(|o| &o[..n_file_bonds_old]);
+    let inferred_orders = structure
+        .bond_orders
+        .as_deref()
+        .map(|o| &o[n_file_bonds_old..]);
+
+    let (file_bonds, file_orders) = remap_bonds(&structure.bonds[..n_file_bonds_old], file_orders);
+    let (inferred_bonds, inferred_orders) =
+        remap_bonds(&structure.bonds[n_file_bonds_old..], inferred_orders);
+
+    let n_file_bonds = file_bonds.len();
+    let mut bonds = file_bonds;
+    bonds.extend(inferred_bonds);
+
+    let bond_orders = match (file_orders, inferred_orders) {
+        (Some(mut fo), Some(io)) => {
+            fo.extend(io);
+            Some(fo)
+        }
+        _ => None,
+    };
+
+    let mut frame_positions = Vec::with_capacity(structure.frame_positions.len());
+    for frame in &structure.frame_positions {
+        let mut new_frame = Vec::with_capacity(keep_atoms.len() * 3);
+        for &old_idx in keep_atoms {
+            let old = old_idx as usize;
+            new_frame.push(frame[old * 3]);
+            new_frame.push(frame[old * 3 + 1]);
+            new_frame.push(frame[old * 3 + 2]);
+        }
+        frame_positions.push(new_frame);
+    }
+
+    let velocities = structure.velocities.as_ref().map(|velocities| {
+        let mut new_velocities = Vec::with_capacity(keep_atoms.len() * 3);
+        for &old_idx in keep_atoms {
+            let old = old_idx as usize;
+            new_velocities.push(velocities[old * 3]);
+            new_velocities.push(velocities[old * 3 + 1]);
+            new_velocities.push(velocities[old * 3 + 2]);
+        }
+        new_velocities
+    });
+
+    structure.n_atoms = keep_atoms.len();
+    structure.positions = positions;
+    structure.elements = elements;
+    structure.formal_charges = formal_charges;
+    structure.bonds = bonds;
+    structure.n_file_bonds = n_file_bonds;
+    structure.bond_orders = bond_orders;
+    structure.frame_positions = frame_positions;
+    structure.velocities = velocities;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn structure_with_water_and_salt() -> ParsedStructure {
+        // Atom 0-2: water (O, H, H), bonded into one fragment.
+        // Atom 3: a lone Na+ ion, its own fragment.
+        ParsedStructure {
+            n_atoms: 4,
+            positions: vec![
+                0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 5.0, 5.0, 5.0,
+            ],
+            elements: vec![8, 1, 1, 11],
+            bonds: vec![(0, 1), (0, 2)],
+            n_file_bonds: 2,
+            bond_orders: None,
+            box_matrix: None,
+            frame_positions: Vec::new(),
+            formal_charges: vec![0, 0, 0, 1],
+            space_group: None,
+            velocities: Some(vec![
+                1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 3.0, 3.0, 3.0, 4.0, 4.0, 4.0,
+            ]),
+        }
+    }
+
+    #[test]
+    fn strip_salts_remaps_velocities_with_remaining_atoms() {
+        let mut structure = structure_with_water_and_salt();
+        // Water is itself in the known-salt/solvent table, so keep the
+        // largest fragment (the water) instead of stripping known salts.
+        let opts = StripOptions {
+            remove_known_salts: false,
+            keep_largest: Some(1),
+        };
+        strip_salts(&mut structure, &opts);
+
+        assert_eq!(structure.n_atoms, 3);
+        let velocities = structure.velocities.expect("velocities should survive stripping");
+        assert_eq!(velocities.len(), structure.n_atoms * 3);
+        // The surviving atoms are the water molecule (old indices 0, 1, 2),
+        // so their velocities should be unchanged and in the same order.
+        assert_eq!(velocities, vec![1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 3.0, 3.0, 3.0]);
+    }
+}
@@ -1,8 +1,12 @@
 pub mod bonds;
+pub mod fragments;
 pub mod gro;
+pub mod mmcif;
 pub mod mol;
 pub mod parser;
+pub mod symmetry;
 pub mod top;
+pub mod writer;
 pub mod xtc;
 pub mod xyz;
 
@@ -0,0 +1,376 @@
+/// mmCIF / PDBx text parser.
+///
+/// A sibling to [`crate::parser`]'s legacy fixed-column PDB reader. Modern
+/// RCSB downloads, and any structure with more than 99,999 atoms or more
+/// than 62 chains (both of which overflow the PDB format's fixed columns),
+/// are only distributed as mmCIF.
+///
+/// This is a minimal tokenizer for the CIF category/item dictionary syntax:
+/// `data_` blocks, single `_category.item value` pairs, and `loop_`
+/// constructs where a run of `_category.item` tags defines columns followed
+/// by whitespace- or quote-delimited data rows. Multi-line values wrapped in
+/// `;`-delimited text blocks are also understood.
+
+use std::collections::HashMap;
+
+use crate::parser::{cell_params_to_matrix, symbol_to_atomic_num, ParsedStructure};
+
+/// A parsed `loop_` construct: the item names (without the shared category
+/// prefix) and the data rows, one token per item.
+struct CifLoop {
+    items: Vec<String>,
+    rows: Vec<Vec<String>>,
+}
+
+impl CifLoop {
+    fn col(&self, item: &str) -> Option<usize> {
+        self.items.iter().position(|i| i == item)
+    }
+}
+
+/// Parse an mmCIF document into single `_category.item value` pairs and
+/// `loop_` tables, keyed by category (e.g. `_atom_site`, `_cell`).
+fn parse_cif(text: &str) -> (HashMap<String, String>, HashMap<String, CifLoop>) {
+    let tokens = tokenize(text);
+    let mut singles: HashMap<String, String> = HashMap::new();
+    let mut loops: HashMap<String, CifLoop> = HashMap::new();
+
+    let mut i = 0;
+    while i < tokens.len() {
+        let tok = tokens[i].as_str();
+
+        if tok == "loop_" {
+            i += 1;
+            let mut tags = Vec::new();
+            while i < tokens.len() && tokens[i].starts_with('_') {
+                tags.push(tokens[i].clone());
+                i += 1;
+            }
+            if tags.is_empty() {
+                continue;
+            }
+            let category = category_of(&tags[0]);
+            let items: Vec<String> = tags.iter().map(|t| item_of(t)).collect();
+            let ncols = tags.len();
+
+            let mut rows = Vec::new();
+            while i < tokens.len() {
+                let t = tokens[i].as_str();
+                if t.starts_with('_') || t == "loop_" || t.starts_with("data_") {
+                    break;
+                }
+                if i + ncols > tokens.len() {
+                    break;
+                }
+                rows.push(tokens[i..i + ncols].to_vec());
+                i += ncols;
+            }
+
+            loops.insert(category, CifLoop { items, rows });
+        } else if let Some(stripped) = tok.strip_prefix("data_") {
+            let _ = stripped; // block name, not needed
+            i += 1;
+        } else if tok.starts_with('_') {
+            let key = tok.to_string();
+            i += 1;
+            if i >= tokens.len() {
+                break;
+            }
+            singles.insert(key, tokens[i].clone());
+            i += 1;
+        } else {
+            i += 1; // stray token (e.g. bare `#`-less comment remnants)
+        }
+    }
+
+    (singles, loops)
+}
+
+fn category_of(tag: &str) -> String {
+    tag.split('.').next().unwrap_or(tag).to_string()
+}
+
+fn item_of(tag: &str) -> String {
+    match tag.find('.') {
+        Some(pos) => tag[pos + 1..].to_string(),
+        None => tag.to_string(),
+    }
+}
+
+/// Tokenize an mmCIF document: whitespace/quote-delimited words, with
+/// `;`-fenced multi-line text blocks collapsed into a single token.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut lines = text.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with(';') {
+            let mut block = String::new();
+            let rest = &line[1..];
+            if !rest.is_empty() {
+                block.push_str(rest);
+            }
+            while let Some(&next) = lines.peek() {
+                if next.starts_with(';') {
+                    lines.next(); // consume the closing `;`
+                    break;
+                }
+                if !block.is_empty() {
+                    block.push('\n');
+                }
+                block.push_str(next);
+                lines.next();
+            }
+            tokens.push(block);
+            continue;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        tokens.extend(tokenize_quoted_line(line));
+    }
+
+    tokens
+}
+
+/// Split one line into tokens, keeping text inside matching `'` or `"`
+/// quotes together as a single token (so category parsing survives
+/// embedded whitespace).
+///
+/// Per the CIF grammar, a quote only *opens* a token at the start of a
+/// word (i.e. it is the first character after whitespace) and only
+/// *closes* it when immediately followed by whitespace or end of line.
+/// A bare `'` elsewhere — e.g. the primed ribose atom names `O3'`, `C1'`
+/// common to every nucleic-acid deposition — is just part of an
+/// unquoted token, not a delimiter.
+fn tokenize_quoted_line(line: &str) -> Vec<String> {
+    let chars: Vec<char> = line.chars().collect();
+    let n = chars.len();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < n {
+        while i < n && chars[i].is_whitespace() {
+            i += 1;
+        }
+        if i >= n {
+            break;
+        }
+
+        if chars[i] == '\'' || chars[i] == '"' {
+            let quote = chars[i];
+            let start = i + 1;
+            let mut j = start;
+            while j < n && !(chars[j] == quote && (j + 1 == n || chars[j + 1].is_whitespace())) {
+                j += 1;
+            }
+            if j < n {
+                tokens.push(chars[start..j].iter().collect());
+                i = j + 1;
+                continue;
+            }
+            // No properly-closed quote on this line: fall through and
+            // treat it as an ordinary unquoted token instead of eating
+            // the rest of the line.
+        }
+
+        let start = i;
+        while i < n && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        tokens.push(chars[start..i].iter().collect());
+    }
+
+    tokens
+}
+
+/// Parse an mmCIF/PDBx document into the same `ParsedStructure` the legacy
+/// PDB path produces.
+pub fn parse_mmcif(text: &str) -> Result<ParsedStructure, String> {
+    let (singles, loops) = parse_cif(text);
+
+    let atom_site = loops
+        .get("_atom_site")
+        .ok_or("mmCIF file has no _atom_site loop")?;
+
+    let type_symbol_col = atom_site
+        .col("type_symbol")
+        .ok_or("_atom_site loop is missing type_symbol")?;
+    let x_col = atom_site
+        .col("Cartn_x")
+        .ok_or("_atom_site loop is missing Cartn_x")?;
+    let y_col = atom_site
+        .col("Cartn_y")
+        .ok_or("_atom_site loop is missing Cartn_y")?;
+    let z_col = atom_site
+        .col("Cartn_z")
+        .ok_or("_atom_site loop is missing Cartn_z")?;
+    let model_col = atom_site.col("pdbx_PDB_model_num");
+    let asym_col = atom_site.col("label_asym_id");
+    let seq_col = atom_site.col("label_seq_id");
+    let atom_id_col = atom_site.col("label_atom_id");
+    let charge_col = atom_site.col("pdbx_formal_charge");
+
+    // Group rows by model number, preserving first-seen order.
+    let mut model_order: Vec<String> = Vec::new();
+    let mut models: HashMap<String, Vec<&Vec<String>>> = HashMap::new();
+    for row in &atom_site.rows {
+        let model = match model_col {
+            Some(c) => row.get(c).cloned().unwrap_or_else(|| "1".to_string()),
+            None => "1".to_string(),
+        };
+        if !models.contains_key(&model) {
+            model_order.push(model.clone());
+        }
+        models.entry(model).or_default().push(row);
+    }
+
+    let first_model_key = model_order
+        .first()
+        .ok_or("mmCIF _atom_site loop has no rows")?;
+    let first_rows = &models[first_model_key];
+    let n_atoms = first_rows.len();
+
+    let mut positions = Vec::with_capacity(n_atoms * 3);
+    let mut elements = Vec::with_capacity(n_atoms);
+    let mut formal_charges = Vec::with_capacity(n_atoms);
+    // (label_asym_id, label_seq_id, label_atom_id) -> atom index, for
+    // resolving optional _struct_conn bond records.
+    let mut atom_key_to_idx: HashMap<(String, String, String), usize> = HashMap::new();
+
+    for (idx, row) in first_rows.iter().enumerate() {
+        let sym = crate::parser::capitalize(&row[type_symbol_col]);
+        elements.push(symbol_to_atomic_num(&sym));
+
+        let x: f32 = row[x_col].parse().map_err(|_| format!("bad Cartn_x '{}'", row[x_col]))?;
+        let y: f32 = row[y_col].parse().map_err(|_| format!("bad Cartn_y '{}'", row[y_col]))?;
+        let z: f32 = row[z_col].parse().map_err(|_| format!("bad Cartn_z '{}'", row[z_col]))?;
+        positions.push(x);
+        positions.push(y);
+        positions.push(z);
+
+        let charge = charge_col
+            .and_then(|c| row.get(c))
+            .and_then(|v| v.parse::<i8>().ok())
+            .unwrap_or(0);
+        formal_charges.push(charge);
+
+        if let (Some(ac), Some(sc), Some(atc)) = (asym_col, seq_col, atom_id_col) {
+            let key = (row[ac].clone(), row[sc].clone(), row[atc].clone());
+            atom_key_to_idx.insert(key, idx);
+        }
+    }
+
+    let mut frame_positions: Vec<Vec<f32>> = Vec::new();
+    for model in model_order.iter().skip(1) {
+        let rows = &models[model];
+        if rows.len() != n_atoms {
+            continue;
+        }
+        let mut frame = Vec::with_capacity(n_atoms * 3);
+        for row in rows {
+            frame.push(row[x_col].parse().unwrap_or(0.0));
+            frame.push(row[y_col].parse().unwrap_or(0.0));
+            frame.push(row[z_col].parse().unwrap_or(0.0));
+        }
+        frame_positions.push(frame);
+    }
+
+    let box_matrix = parse_cell(&singles);
+    let space_group = singles
+        .get("_symmetry.space_group_name_H-M")
+        .map(|s| s.to_string());
+
+    let mut bonds = parse_struct_conn(&loops, &atom_key_to_idx);
+    let n_file_bonds = bonds.len();
+    let existing: std::collections::HashSet<(u32, u32)> = bonds.iter().copied().collect();
+    let inferred = crate::bonds::infer_bonds(&positions, &elements, n_atoms, &existing);
+    bonds.extend(inferred);
+
+    let bond_orders =
+        crate::bonds::perceive_bond_orders(&positions, &elements, &bonds, &formal_charges);
+
+    Ok(ParsedStructure {
+        n_atoms,
+        positions,
+        elements,
+        bonds,
+        n_file_bonds,
+        bond_orders: Some(bond_orders),
+        box_matrix,
+        frame_positions,
+        formal_charges,
+        space_group,
+        velocities: None,
+    })
+}
+
+/// Build `box_matrix` from `_cell.length_a/b/c` and `_cell.angle_alpha/beta/gamma`.
+fn parse_cell(singles: &HashMap<String, String>) -> Option<[f32; 9]> {
+    let a: f32 = singles.get("_cell.length_a")?.parse().ok()?;
+    let b: f32 = singles.get("_cell.length_b")?.parse().ok()?;
+    let c: f32 = singles.get("_cell.length_c")?.parse().ok()?;
+    let alpha: f32 = singles.get("_cell.angle_alpha")?.parse().ok()?;
+    let beta: f32 = singles.get("_cell.angle_beta")?.parse().ok()?;
+    let gamma: f32 = singles.get("_cell.angle_gamma")?.parse().ok()?;
+    if a <= 0.0 || b <= 0.0 || c <= 0.0 {
+        return None;
+    }
+    Some(cell_params_to_matrix(a, b, c, alpha, beta, gamma))
+}
+
+/// Seed bonds from an optional `_struct_conn` loop, resolving each partner
+/// by its `(label_asym_id, label_seq_id, label_atom_id)` key.
+fn parse_struct_conn(
+    loops: &HashMap<String, CifLoop>,
+    atom_key_to_idx: &HashMap<(String, String, String), usize>,
+) -> Vec<(u32, u32)> {
+    let Some(conn) = loops.get("_struct_conn") else {
+        return Vec::new();
+    };
+    let (Some(asym1), Some(seq1), Some(atom1), Some(asym2), Some(seq2), Some(atom2)) = (
+        conn.col("ptnr1_label_asym_id"),
+        conn.col("ptnr1_label_seq_id"),
+        conn.col("ptnr1_label_atom_id"),
+        conn.col("ptnr2_label_asym_id"),
+        conn.col("ptnr2_label_seq_id"),
+        conn.col("ptnr2_label_atom_id"),
+    ) else {
+        return Vec::new();
+    };
+
+    let mut bonds = Vec::new();
+    for row in &conn.rows {
+        let key1 = (row[asym1].clone(), row[seq1].clone(), row[atom1].clone());
+        let key2 = (row[asym2].clone(), row[seq2].clone(), row[atom2].clone());
+        if let (Some(&i), Some(&j)) = (atom_key_to_idx.get(&key1), atom_key_to_idx.get(&key2)) {
+            if i != j {
+                bonds.push((i.min(j) as u32, i.max(j) as u32));
+            }
+        }
+    }
+    bonds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primed_atom_names_are_not_treated_as_quotes() {
+        let line = "ATOM 1 C C1' . DC A 1 1 ? -1.0 2.0 3.0 1.00 0.00 ? 1 DC A C1' 1";
+        let tokens = tokenize_quoted_line(line);
+        assert_eq!(tokens.len(), 21);
+        assert_eq!(tokens[3], "C1'");
+        assert_eq!(tokens[19], "C1'");
+    }
+
+    #[test]
+    fn quoted_value_with_embedded_whitespace_survives() {
+        let tokens = tokenize_quoted_line("_struct.title 'a value with spaces'");
+        assert_eq!(tokens, vec!["_struct.title", "a value with spaces"]);
+    }
+}
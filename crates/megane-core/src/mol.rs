@@ -0,0 +1,418 @@
+/// MDL Molfile (V2000/V3000) parser.
+///
+/// V2000 format:
+///   Lines 1-3: header (molecule name, program/timestamp, comment)
+///   Line 4: counts line (natoms nbonds ... version)
+///   Lines 5..4+natoms: atom block (x y z symbol ...)
+///   Lines 5+natoms..4+natoms+nbonds: bond block (atom1 atom2 bond_order ...)
+///   M  END
+///
+/// V3000 format (used when the counts line ends in `V3000`, required once a
+/// molecule has more than 999 atoms or bonds, which overflow the V2000
+/// 3-digit count fields):
+///   Line 4: counts line ending in `V3000` (the atom/bond counts themselves
+///     are ignored, as is the V3000 `COUNTS` line; the counts are those of
+///     the atom and bond lines actually read)
+///   `M  V30 BEGIN ATOM` .. `M  V30 END ATOM`: `index symbol x y z ...`
+///   `M  V30 BEGIN BOND` .. `M  V30 END BOND`: `index order atom1 atom2`
+
+use std::collections::HashMap;
+
+use crate::parser::symbol_to_atomic_num;
+
+pub fn parse(text: &str) -> Result<crate::parser::ParsedStructure, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() < 5 {
+        return Err("MOL file too short".into());
+    }
+
+    let counts_line = lines[3];
+    if counts_line.trim_end().ends_with("V3000") {
+        return parse_v3000(&lines);
+    }
+    parse_v2000(&lines, counts_line)
+}
+
+fn parse_v2000(lines: &[&str], counts_line: &str) -> Result<crate::parser::ParsedStructure, String> {
+    let n_atoms = parse_mol_int(counts_line, 0, 3)?;
+    let n_bonds = parse_mol_int(counts_line, 3, 6)?;
+
+    if n_atoms == 0 {
+        return Err("MOL file has zero atoms".into());
+    }
+
+    let atom_start = 4;
+    let bond_start = atom_start + n_atoms;
+
+    if lines.len() < bond_start + n_bonds {
+        return Err(format!(
+            "MOL file too short: expected {} atom + {} bond lines",
+            n_atoms, n_bonds
+        ));
+    }
+
+    // Parse atom block
+    let mut positions = Vec::with_capacity(n_atoms * 3);
+    let mut elements = Vec::with_capacity(n_atoms);
+
+    for i in 0..n_atoms {
+        let line = lines[atom_start + i];
+        // V2000 atom line: x(10.4) y(10.4) z(10.4) symbol(3) ...
+        // Columns: 0-9 x, 10-19 y, 20-29 z, 31-33 symbol
+        if line.len() < 34 {
+            // Fall back to whitespace splitting for short/non-standard lines
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 4 {
+                return Err(format!("MOL atom line {} too short", i + 1));
+            }
+            let x: f32 = parts[0].parse().map_err(|_| format!("bad x at atom {}", i + 1))?;
+            let y: f32 = parts[1].parse().map_err(|_| format!("bad y at atom {}", i + 1))?;
+            let z: f32 = parts[2].parse().map_err(|_| format!("bad z at atom {}", i + 1))?;
+            let sym = crate::parser::capitalize(parts[3]);
+            positions.push(x);
+            positions.push(y);
+            positions.push(z);
+            elements.push(symbol_to_atomic_num(&sym));
+        } else {
+            let x: f32 = line[0..10]
+                .trim()
+                .parse()
+                .map_err(|_| format!("bad x at atom {}", i + 1))?;
+            let y: f32 = line[10..20]
+                .trim()
+                .parse()
+                .map_err(|_| format!("bad y at atom {}", i + 1))?;
+            let z: f32 = line[20..30]
+                .trim()
+                .parse()
+                .map_err(|_| format!("bad z at atom {}", i + 1))?;
+            let sym = crate::parser::capitalize(line[31..34].trim());
+            positions.push(x);
+            positions.push(y);
+            positions.push(z);
+            elements.push(symbol_to_atomic_num(&sym));
+        }
+    }
+
+    // Parse bond block
+    let mut bonds = Vec::with_capacity(n_bonds);
+    let mut bond_orders = Vec::with_capacity(n_bonds);
+
+    for i in 0..n_bonds {
+        let line = lines[bond_start + i];
+        // V2000 bond line: atom1(3) atom2(3) bond_type(3) ...
+        if line.len() < 9 {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() < 3 {
+                return Err(format!("MOL bond line {} too short", i + 1));
+            }
+            let a: u32 = parts[0].parse::<u32>().map_err(|_| format!("bad bond atom1 at bond {}", i + 1))? - 1;
+            let b: u32 = parts[1].parse::<u32>().map_err(|_| format!("bad bond atom2 at bond {}", i + 1))? - 1;
+            let order: u8 = parts[2].parse().unwrap_or(1);
+            bonds.push((a.min(b), a.max(b)));
+            bond_orders.push(order);
+        } else {
+            let a = parse_mol_int(line, 0, 3)? as u32 - 1;
+            let b = parse_mol_int(line, 3, 6)? as u32 - 1;
+            let order = parse_mol_int(line, 6, 9).unwrap_or(1) as u8;
+            bonds.push((a.min(b), a.max(b)));
+            bond_orders.push(order);
+        }
+    }
+
+    Ok(crate::parser::ParsedStructure {
+        n_atoms,
+        positions,
+        elements,
+        bonds,
+        n_file_bonds: n_bonds,
+        bond_orders: Some(bond_orders),
+        box_matrix: None,
+        frame_positions: Vec::new(),
+        formal_charges: vec![0; n_atoms],
+        space_group: None,
+        velocities: None,
+    })
+}
+
+/// Parse the tagged V3000 block format: `M  V30` lines between
+/// `BEGIN ATOM`/`END ATOM` and `BEGIN BOND`/`END BOND` markers.
+fn parse_v3000(lines: &[&str]) -> Result<crate::parser::ParsedStructure, String> {
+    let mut positions: Vec<f32> = Vec::new();
+    let mut elements: Vec<u8> = Vec::new();
+    let mut bonds: Vec<(u32, u32)> = Vec::new();
+    let mut bond_orders: Vec<u8> = Vec::new();
+    let mut n_atoms_seen = 0usize;
+
+    #[derive(PartialEq)]
+    enum Block {
+        None,
+        Atom,
+        Bond,
+    }
+    let mut block = Block::None;
+
+    for raw in lines {
+        let line = raw.trim();
+        let Some(rest) = line.strip_prefix("M  V30 ") else {
+            continue;
+        };
+        let rest = rest.trim();
+
+        match rest {
+            "BEGIN ATOM" => {
+                block = Block::Atom;
+                continue;
+            }
+            "END ATOM" => {
+                block = Block::None;
+                continue;
+            }
+            "BEGIN BOND" => {
+                block = Block::Bond;
+                continue;
+            }
+            "END BOND" => {
+                block = Block::None;
+                continue;
+            }
+            _ => {}
+        }
+
+        match block {
+            Block::Atom => {
+                // index symbol x y z ...
+                let parts: Vec<&str> = rest.split_whitespace().collect();
+                if parts.len() < 5 {
+                    return Err(format!("V3000 atom line too short: '{}'", rest));
+                }
+                let sym = crate::parser::capitalize(parts[1]);
+                let x: f32 = parts[2].parse().map_err(|_| format!("bad x in '{}'", rest))?;
+                let y: f32 = parts[3].parse().map_err(|_| format!("bad y in '{}'", rest))?;
+                let z: f32 = parts[4].parse().map_err(|_| format!("bad z in '{}'", rest))?;
+                positions.push(x);
+                positions.push(y);
+                positions.push(z);
+                elements.push(symbol_to_atomic_num(&sym));
+                n_atoms_seen += 1;
+            }
+            Block::Bond => {
+                // index order atom1 atom2 ...
+                let parts: Vec<&str> = rest.split_whitespace().collect();
+                if parts.len() < 4 {
+                    return Err(format!("V3000 bond line too short: '{}'", rest));
+                }
+                let order: u8 = parts[1].parse().unwrap_or(1);
+                let a: u32 = parts[2]
+                    .parse::<u32>()
+                    .map_err(|_| format!("bad bond atom1 in '{}'", rest))?
+                    - 1;
+                let b: u32 = parts[3]
+                    .parse::<u32>()
+                    .map_err(|_| format!("bad bond atom2 in '{}'", rest))?
+                    - 1;
+                bonds.push((a.min(b), a.max(b)));
+                bond_orders.push(order);
+            }
+            Block::None => {}
+        }
+    }
+
+    if n_atoms_seen == 0 {
+        return Err("V3000 MOL file has zero atoms".into());
+    }
+
+    let n_bonds = bonds.len();
+    Ok(crate::parser::ParsedStructure {
+        n_atoms: n_atoms_seen,
+        positions,
+        elements,
+        bonds,
+        n_file_bonds: n_bonds,
+        bond_orders: Some(bond_orders),
+        box_matrix: None,
+        frame_positions: Vec::new(),
+        formal_charges: vec![0; n_atoms_seen],
+        space_group: None,
+        velocities: None,
+    })
+}
+
+/// Parse a multi-record SDF file: each record is a V2000/V3000 molblock
+/// followed by optional `> <FieldName>` data items, terminated by a `$$$$`
+/// line. Returns one `(ParsedStructure, data fields)` pair per record.
+pub fn parse_sdf(text: &str) -> Result<Vec<(crate::parser::ParsedStructure, HashMap<String, String>)>, String> {
+    let mut records = Vec::new();
+
+    for raw_record in split_records(text) {
+        let (molblock, data_block) = match raw_record.find("\nM  END") {
+            Some(pos) => {
+                // Keep the "M  END" line itself as part of the molblock, the
+                // rest (data fields) belongs to the tag block.
+                let end = pos + "\nM  END".len();
+                (&raw_record[..end], &raw_record[end..])
+            }
+            None => (raw_record.as_str(), ""),
+        };
+
+        let structure = parse(molblock)?;
+        let fields = parse_sdf_data_fields(data_block);
+        records.push((structure, fields));
+    }
+
+    if records.is_empty() {
+        return Err("SDF file contains no records".into());
+    }
+
+    Ok(records)
+}
+
+/// Split SDF text into per-molecule chunks on `$$$$` terminator lines.
+fn split_records(text: &str) -> Vec<String> {
+    let mut records = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines() {
+        if line.trim_end() == "$$$$" {
+            if !current.trim().is_empty() {
+                records.push(std::mem::take(&mut current));
+            } else {
+                current.clear();
+            }
+            continue;
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+
+    if !current.trim().is_empty() {
+        records.push(current);
+    }
+
+    records
+}
+
+/// Parse `> <FieldName>` / value blocks into a tag → value map.
+fn parse_sdf_data_fields(text: &str) -> HashMap<String, String> {
+    let mut fields = HashMap::new();
+    let mut current_tag: Option<String> = None;
+    let mut current_value = String::new();
+
+    for line in text.lines() {
+        if let Some(tag) = parse_data_header(line) {
+            if let Some(tag) = current_tag.take() {
+                fields.insert(tag, current_value.trim_end().to_string());
+            }
+            current_tag = Some(tag);
+            current_value = String::new();
+        } else if current_tag.is_some() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if !current_value.is_empty() {
+                current_value.push('\n');
+            }
+            current_value.push_str(line);
+        }
+    }
+
+    if let Some(tag) = current_tag {
+        fields.insert(tag, current_value.trim_end().to_string());
+    }
+
+    fields
+}
+
+/// Extract the field name from a `> <FieldName>` header line.
+fn parse_data_header(line: &str) -> Option<String> {
+    let line = line.trim();
+    if !line.starts_with('>') {
+        return None;
+    }
+    let start = line.find('<')?;
+    let end = line[start + 1..].find('>')? + start + 1;
+    Some(line[start + 1..end].to_string())
+}
+
+/// Parse an integer from a fixed-width field in a MOL file line.
+fn parse_mol_int(line: &str, start: usize, end: usize) -> Result<usize, String> {
+    let end = end.min(line.len());
+    if start >= end {
+        return Err(format!("field {}..{} out of range", start, end));
+    }
+    line[start..end]
+        .trim()
+        .parse()
+        .map_err(|_| format!("cannot parse integer from '{}'", &line[start..end]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_v3000_reads_atoms_and_bonds() {
+        let text = "\
+mol
+  test
+
+  0  0  0  0  0  0  0  0  0  0999 V3000
+M  V30 BEGIN CTAB
+M  V30 COUNTS 2 1 0 0 0
+M  V30 BEGIN ATOM
+M  V30 1 C 0.0 0.0 0.0 0
+M  V30 2 O 1.2 0.0 0.0 0
+M  V30 END ATOM
+M  V30 BEGIN BOND
+M  V30 1 2 1 2
+M  V30 END BOND
+M  V30 END CTAB
+M  END
+";
+
+        let structure = parse(text).expect("V3000 molfile should parse");
+        assert_eq!(structure.n_atoms, 2);
+        assert_eq!(structure.elements, vec![6, 8]);
+        assert_eq!(structure.bonds, vec![(0, 1)]);
+        assert_eq!(structure.bond_orders, Some(vec![2]));
+    }
+
+    #[test]
+    fn parse_sdf_splits_records_and_data_fields() {
+        let text = "\
+mol1
+  test
+
+  1  0  0  0  0  0  0  0  0  0999 V2000
+    0.0000    0.0000    0.0000 C   0  0  0  0  0  0  0  0  0  0  0  0
+M  END
+> <Name>
+first
+
+$$$$
+mol2
+  test
+
+  1  0  0  0  0  0  0  0  0  0999 V2000
+    1.0000    1.0000    1.0000 O   0  0  0  0  0  0  0  0  0  0  0  0
+M  END
+> <Name>
+second
+
+$$$$
+";
+
+        let records = parse_sdf(text).expect("sdf should parse");
+        assert_eq!(records.len(), 2);
+
+        let (structure0, fields0) = &records[0];
+        assert_eq!(structure0.n_atoms, 1);
+        assert_eq!(structure0.elements, vec![6]);
+        assert_eq!(fields0.get("Name"), Some(&"first".to_string()));
+
+        let (structure1, fields1) = &records[1];
+        assert_eq!(structure1.n_atoms, 1);
+        assert_eq!(structure1.elements, vec![8]);
+        assert_eq!(fields1.get("Name"), Some(&"second".to_string()));
+    }
+}
@@ -0,0 +1,307 @@
+/// Space-group symmetry expansion.
+///
+/// `parse`/`parse_mmcif` only ever return the asymmetric unit recorded in
+/// the file (CRYST1's cell geometry is read, but its space-group symbol is
+/// just carried through as `ParsedStructure::space_group`). This module is
+/// an explicit, opt-in second pass: look up the symbol's operators with
+/// [`lookup`], then call [`expand_unit_cell`] to apply them and produce the
+/// full unit cell (optionally tiled into a supercell).
+
+use std::collections::HashSet;
+
+use crate::bonds;
+use crate::parser::ParsedStructure;
+
+/// One (rotation, translation) symmetry operator in fractional coordinates:
+/// `frac' = rot * frac + trans`.
+pub struct SymmetryOp {
+    pub rot: [[i8; 3]; 3],
+    pub trans: [f32; 3],
+}
+
+macro_rules! op {
+    ($r00:expr, $r01:expr, $r02:expr, $r10:expr, $r11:expr, $r12:expr, $r20:expr, $r21:expr, $r22:expr, $t0:expr, $t1:expr, $t2:expr) => {
+        SymmetryOp {
+            rot: [[$r00, $r01, $r02], [$r10, $r11, $r12], [$r20, $r21, $r22]],
+            trans: [$t0, $t1, $t2],
+        }
+    };
+}
+
+/// Look up the symmetry operators for a common Hermann-Mauguin space-group
+/// symbol (e.g. `"P 21 21 21"` or `"P212121"` - whitespace and letter case
+/// are ignored, and common short/full-symbol spellings are both
+/// recognized). Covers the space groups most often seen in small-molecule
+/// and macromolecular depositions; returns `None` for anything else.
+pub fn lookup(symbol: &str) -> Option<Vec<SymmetryOp>> {
+    let key: String = symbol
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+
+    let ops = match key.as_str() {
+        "P1" => vec![op!(1, 0, 0, 0, 1, 0, 0, 0, 1, 0.0, 0.0, 0.0)],
+
+        "P-1" => vec![
+            op!(1, 0, 0, 0, 1, 0, 0, 0, 1, 0.0, 0.0, 0.0),
+            op!(-1, 0, 0, 0, -1, 0, 0, 0, -1, 0.0, 0.0, 0.0),
+        ],
+
+        "P2" | "P121" => vec![
+            op!(1, 0, 0, 0, 1, 0, 0, 0, 1, 0.0, 0.0, 0.0),
+            op!(-1, 0, 0, 0, 1, 0, 0, 0, -1, 0.0, 0.0, 0.0),
+        ],
+
+        "P21" | "P1211" => vec![
+            op!(1, 0, 0, 0, 1, 0, 0, 0, 1, 0.0, 0.0, 0.0),
+            op!(-1, 0, 0, 0, 1, 0, 0, 0, -1, 0.0, 0.5, 0.0),
+        ],
+
+        "C2" | "C121" => vec![
+            op!(1, 0, 0, 0, 1, 0, 0, 0, 1, 0.0, 0.0, 0.0),
+            op!(-1, 0, 0, 0, 1, 0, 0, 0, -1, 0.0, 0.0, 0.0),
+            op!(1, 0, 0, 0, 1, 0, 0, 0, 1, 0.5, 0.5, 0.0),
+            op!(-1, 0, 0, 0, 1, 0, 0, 0, -1, 0.5, 0.5, 0.0),
+        ],
+
+        "P21/C" | "P121/C1" => vec![
+            op!(1, 0, 0, 0, 1, 0, 0, 0, 1, 0.0, 0.0, 0.0),
+            op!(-1, 0, 0, 0, 1, 0, 0, 0, -1, 0.0, 0.5, 0.5),
+            op!(-1, 0, 0, 0, -1, 0, 0, 0, -1, 0.0, 0.0, 0.0),
+            op!(1, 0, 0, 0, -1, 0, 0, 0, 1, 0.0, 0.5, 0.5),
+        ],
+
+        "C2/C" | "C12/C1" => vec![
+            op!(1, 0, 0, 0, 1, 0, 0, 0, 1, 0.0, 0.0, 0.0),
+            op!(-1, 0, 0, 0, 1, 0, 0, 0, -1, 0.0, 0.0, 0.5),
+            op!(-1, 0, 0, 0, -1, 0, 0, 0, -1, 0.0, 0.0, 0.0),
+            op!(1, 0, 0, 0, -1, 0, 0, 0, 1, 0.0, 0.0, 0.5),
+            op!(1, 0, 0, 0, 1, 0, 0, 0, 1, 0.5, 0.5, 0.0),
+            op!(-1, 0, 0, 0, 1, 0, 0, 0, -1, 0.5, 0.5, 0.5),
+            op!(-1, 0, 0, 0, -1, 0, 0, 0, -1, 0.5, 0.5, 0.0),
+            op!(1, 0, 0, 0, -1, 0, 0, 0, 1, 0.5, 0.5, 0.5),
+        ],
+
+        "P212121" => vec![
+            op!(1, 0, 0, 0, 1, 0, 0, 0, 1, 0.0, 0.0, 0.0),
+            op!(-1, 0, 0, 0, -1, 0, 0, 0, 1, 0.5, 0.0, 0.5),
+            op!(-1, 0, 0, 0, 1, 0, 0, 0, -1, 0.0, 0.5, 0.5),
+            op!(1, 0, 0, 0, -1, 0, 0, 0, -1, 0.5, 0.5, 0.0),
+        ],
+
+        _ => return None,
+    };
+
+    Some(ops)
+}
+
+fn cart_to_frac(x: f32, y: f32, z: f32, inv: &[f32; 9]) -> (f32, f32, f32) {
+    let f0 = x * inv[0] + y * inv[3] + z * inv[6];
+    let f1 = x * inv[1] + y * inv[4] + z * inv[7];
+    let f2 = x * inv[2] + y * inv[5] + z * inv[8];
+    (f0, f1, f2)
+}
+
+fn frac_to_cart(f0: f32, f1: f32, f2: f32, box_matrix: &[f32; 9]) -> (f32, f32, f32) {
+    let x = f0 * box_matrix[0] + f1 * box_matrix[3] + f2 * box_matrix[6];
+    let y = f0 * box_matrix[1] + f1 * box_matrix[4] + f2 * box_matrix[7];
+    let z = f0 * box_matrix[2] + f1 * box_matrix[5] + f2 * box_matrix[8];
+    (x, y, z)
+}
+
+/// Apply `op` to a fractional coordinate: `frac' = rot * frac + trans`.
+fn apply_op(op: &SymmetryOp, f0: f32, f1: f32, f2: f32) -> (f32, f32, f32) {
+    let r = &op.rot;
+    (
+        r[0][0] as f32 * f0 + r[0][1] as f32 * f1 + r[0][2] as f32 * f2 + op.trans[0],
+        r[1][0] as f32 * f0 + r[1][1] as f32 * f1 + r[1][2] as f32 * f2 + op.trans[1],
+        r[2][0] as f32 * f0 + r[2][1] as f32 * f1 + r[2][2] as f32 * f2 + op.trans[2],
+    )
+}
+
+/// Expand `structure`'s asymmetric unit into a full unit cell by applying
+/// `ops`, then tile the result by `supercell` integer lattice translations
+/// (each component treated as at least 1). Coinciding atoms (common on
+/// special positions) are kept once: each Cartesian coordinate is rounded
+/// to a grid of spacing `tolerance`, and a copy landing in an occupied grid
+/// point is dropped. Copies closer than `tolerance` that round to different
+/// grid points are both kept.
+/// `positions`, `elements`, `formal_charges`, `bonds`, and every entry of
+/// `frame_positions` are rebuilt to match; `bonds` is then re-inferred from
+/// scratch so contacts between symmetry mates become visible. `velocities`
+/// has no well-defined value for the new, symmetry-generated atoms, so it
+/// is cleared to `None` rather than left sized for the old atom count.
+pub fn expand_unit_cell(
+    structure: &mut ParsedStructure,
+    ops: &[SymmetryOp],
+    supercell: (u32, u32, u32),
+    tolerance: f32,
+) -> Result<(), String> {
+    let box_matrix = structure
+        .box_matrix
+        .ok_or("symmetry expansion requires a box_matrix")?;
+    let inv = bonds::invert3(&box_matrix).ok_or("box_matrix is singular")?;
+
+    let (nx, ny, nz) = (supercell.0.max(1), supercell.1.max(1), supercell.2.max(1));
+
+    // (original atom index, op index, tile offset) for every surviving
+    // copy, in generation order - reused to expand frame_positions the
+    // same way.
+    let mut sources: Vec<(usize, usize, (u32, u32, u32))> = Vec::new();
+    let mut seen: HashSet<(i64, i64, i64)> = HashSet::new();
+
+    let mut positions = Vec::new();
+    let mut elements = Vec::new();
+    let mut formal_charges = Vec::new();
+
+    let bucket = |v: f32| -> i64 { (v / tolerance).round() as i64 };
+
+    for atom_idx in 0..structure.n_atoms {
+        let (x, y, z) = (
+            structure.positions[atom_idx * 3],
+            structure.positions[atom_idx * 3 + 1],
+            structure.positions[atom_idx * 3 + 2],
+        );
+        let (fx, fy, fz) = cart_to_frac(x, y, z, &inv);
+
+        for (op_idx, op) in ops.iter().enumerate() {
+            let (rf0, rf1, rf2) = apply_op(op, fx, fy, fz);
+            let (wf0, wf1, wf2) = (rf0 - rf0.floor(), rf1 - rf1.floor(), rf2 - rf2.floor());
+
+            for tx in 0..nx {
+                for ty in 0..ny {
+                    for tz in 0..nz {
+                        let (cx, cy, cz) = frac_to_cart(
+                            wf0 + tx as f32,
+                            wf1 + ty as f32,
+                            wf2 + tz as f32,
+                            &box_matrix,
+                        );
+
+                        if !seen.insert((bucket(cx), bucket(cy), bucket(cz))) {
+                            continue;
+                        }
+
+                        positions.push(cx);
+                        positions.push(cy);
+                        positions.push(cz);
+                        elements.push(structure.elements[atom_idx]);
+                        formal_charges.push(structure.formal_charges[atom_idx]);
+                        sources.push((atom_idx, op_idx, (tx, ty, tz)));
+                    }
+                }
+            }
+        }
+    }
+
+    let new_n_atoms = positions.len() / 3;
+
+    let mut frame_positions = Vec::with_capacity(structure.frame_positions.len());
+    for frame in &structure.frame_positions {
+        let mut new_frame = Vec::with_capacity(new_n_atoms * 3);
+        for &(atom_idx, op_idx, (tx, ty, tz)) in &sources {
+            let (x, y, z) = (
+                frame[atom_idx * 3],
+                frame[atom_idx * 3 + 1],
+                frame[atom_idx * 3 + 2],
+            );
+            let (fx, fy, fz) = cart_to_frac(x, y, z, &inv);
+            let (rf0, rf1, rf2) = apply_op(&ops[op_idx], fx, fy, fz);
+            let (cx, cy, cz) = frac_to_cart(
+                rf0 - rf0.floor() + tx as f32,
+                rf1 - rf1.floor() + ty as f32,
+                rf2 - rf2.floor() + tz as f32,
+                &box_matrix,
+            );
+            new_frame.push(cx);
+            new_frame.push(cy);
+            new_frame.push(cz);
+        }
+        frame_positions.push(new_frame);
+    }
+
+    let mut new_box_matrix = box_matrix;
+    for i in 0..3 {
+        new_box_matrix[i] *= nx as f32;
+    }
+    for i in 3..6 {
+        new_box_matrix[i] *= ny as f32;
+    }
+    for i in 6..9 {
+        new_box_matrix[i] *= nz as f32;
+    }
+
+    let no_existing_bonds = HashSet::new();
+    let bonds_expanded = bonds::infer_bonds(&positions, &elements, new_n_atoms, &no_existing_bonds);
+
+    structure.n_atoms = new_n_atoms;
+    structure.positions = positions;
+    structure.elements = elements;
+    structure.formal_charges = formal_charges;
+    structure.bonds = bonds_expanded;
+    structure.n_file_bonds = 0;
+    structure.bond_orders = None;
+    structure.box_matrix = Some(new_box_matrix);
+    structure.frame_positions = frame_positions;
+    // Velocities are sized for the pre-expansion atom count and have no
+    // well-defined value for symmetry-generated copies; drop them rather
+    // than leave a stale, mis-sized buffer behind.
+    structure.velocities = None;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn single_atom_structure() -> ParsedStructure {
+        ParsedStructure {
+            n_atoms: 1,
+            positions: vec![2.5, 2.5, 2.5],
+            elements: vec![6],
+            bonds: Vec::new(),
+            n_file_bonds: 0,
+            bond_orders: None,
+            box_matrix: Some([10.0, 0.0, 0.0, 0.0, 10.0, 0.0, 0.0, 0.0, 10.0]),
+            frame_positions: Vec::new(),
+            formal_charges: vec![0],
+            space_group: Some("P-1".to_string()),
+            velocities: None,
+        }
+    }
+
+    #[test]
+    fn lookup_recognizes_common_symbols_and_ignores_whitespace() {
+        assert!(lookup("P1").is_some());
+        assert_eq!(lookup("P 21 21 21").unwrap().len(), 4);
+        assert!(lookup("not a space group").is_none());
+    }
+
+    #[test]
+    fn expand_unit_cell_applies_inversion_center() {
+        let mut structure = single_atom_structure();
+        let ops = lookup("P-1").unwrap();
+        expand_unit_cell(&mut structure, &ops, (1, 1, 1), 0.01).unwrap();
+
+        assert_eq!(structure.n_atoms, 2);
+        // The inversion of (0.25, 0.25, 0.25) frac is (-0.25, -0.25, -0.25),
+        // wrapped into the cell at (0.75, 0.75, 0.75) -> 7.5 Angstroms.
+        assert_eq!(structure.positions[0..3], [2.5, 2.5, 2.5]);
+        assert!((structure.positions[3] - 7.5).abs() < 1e-4);
+        assert!((structure.positions[4] - 7.5).abs() < 1e-4);
+        assert!((structure.positions[5] - 7.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn expand_unit_cell_clears_velocities_sized_for_old_atom_count() {
+        let mut structure = single_atom_structure();
+        structure.velocities = Some(vec![1.0, 2.0, 3.0]);
+        let ops = lookup("P-1").unwrap();
+        expand_unit_cell(&mut structure, &ops, (1, 1, 1), 0.01).unwrap();
+
+        assert_eq!(structure.n_atoms, 2);
+        assert!(structure.velocities.is_none());
+    }
+}
@@ -1,6 +1,11 @@
 /// GROMACS .top topology file parser.
 ///
-/// Extracts bond pairs from the [ bonds ] section.
+/// [`parse_top_bonds`] is the original, minimal reader: it only looks at
+/// the first `[ bonds ]` section and ignores everything else, which is
+/// enough for single-molecule files but wrong for a real topology built
+/// from `#include`d `.itp` force-field files and a `[ molecules ]` count
+/// list. [`parse_top`] handles the full thing: preprocessing, per-
+/// `[ moleculetype ]` accumulation, and `[ molecules ]` expansion.
 
 /// Parse a GROMACS .top file and extract bond pairs.
 /// Returns Vec<(u32, u32)> with 0-indexed atom pairs.
@@ -65,6 +70,274 @@ pub fn parse_top_bonds(text: &str, n_atoms: usize) -> Vec<(u32, u32)> {
     bonds
 }
 
+/// A fully expanded topology: bonds/angles/dihedrals across every molecule
+/// instance declared in `[ molecules ]`, with atom indices offset so they
+/// refer into the concatenated whole-system atom list (0-indexed).
+pub struct Topology {
+    pub bonds: Vec<(u32, u32)>,
+    pub angles: Vec<(u32, u32, u32)>,
+    /// Proper and improper entries from `[ dihedrals ]`, undistinguished -
+    /// both are 4-atom tuples, and only the GROMACS function-type column
+    /// (which this parser does not track) tells them apart.
+    pub dihedrals: Vec<(u32, u32, u32, u32)>,
+}
+
+/// Bonds/angles/dihedrals (local to the molecule, 0-indexed) and atom
+/// count for a single `[ moleculetype ]` block.
+struct MoleculeTopology {
+    n_atoms: usize,
+    bonds: Vec<(u32, u32)>,
+    angles: Vec<(u32, u32, u32)>,
+    dihedrals: Vec<(u32, u32, u32, u32)>,
+}
+
+impl MoleculeTopology {
+    fn new() -> Self {
+        MoleculeTopology {
+            n_atoms: 0,
+            bonds: Vec::new(),
+            angles: Vec::new(),
+            dihedrals: Vec::new(),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Section {
+    None,
+    MoleculeType,
+    Atoms,
+    Bonds,
+    Angles,
+    Dihedrals,
+    Molecules,
+    Other,
+}
+
+/// Parse a GROMACS `.top` file into a fully expanded [`Topology`]: run a
+/// minimal C-style preprocessor (`#include "file"` resolved against
+/// `include_dirs`, plus `#define`/`#ifdef`/`#ifndef`/`#else`/`#endif`),
+/// accumulate bonds/angles/dihedrals per `[ moleculetype ]`, then replicate
+/// each per its count in the file's `[ molecules ]` section with a running
+/// atom-index offset.
+pub fn parse_top(text: &str, include_dirs: &[String]) -> Result<Topology, String> {
+    let mut defines: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let expanded = preprocess(text, include_dirs, &mut defines)?;
+
+    let mut molecules: std::collections::HashMap<String, MoleculeTopology> =
+        std::collections::HashMap::new();
+    let mut instances: Vec<(String, usize)> = Vec::new();
+    let mut current: Option<String> = None;
+    let mut section = Section::None;
+    // `[ moleculetype ]`'s data line ("name nrexcl") only appears on the
+    // line after the header, so remember that we just saw one.
+    let mut awaiting_moleculetype_name = false;
+
+    for line in expanded.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with(';') {
+            continue;
+        }
+
+        if trimmed.starts_with('[') {
+            let name = trimmed
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .trim()
+                .to_lowercase();
+            section = match name.as_str() {
+                "moleculetype" => {
+                    awaiting_moleculetype_name = true;
+                    Section::MoleculeType
+                }
+                "atoms" => Section::Atoms,
+                "bonds" => Section::Bonds,
+                "angles" => Section::Angles,
+                "dihedrals" => Section::Dihedrals,
+                "molecules" => Section::Molecules,
+                _ => Section::Other,
+            };
+            continue;
+        }
+
+        let data = match trimmed.find(';') {
+            Some(pos) => trimmed[..pos].trim(),
+            None => trimmed,
+        };
+        if data.is_empty() {
+            continue;
+        }
+        let parts: Vec<&str> = data.split_whitespace().collect();
+
+        match section {
+            Section::MoleculeType if awaiting_moleculetype_name => {
+                awaiting_moleculetype_name = false;
+                if let Some(&name) = parts.first() {
+                    molecules
+                        .entry(name.to_string())
+                        .or_insert_with(MoleculeTopology::new);
+                    current = Some(name.to_string());
+                }
+            }
+            Section::Atoms => {
+                if let Some(mol) = current.as_ref().and_then(|name| molecules.get_mut(name)) {
+                    mol.n_atoms += 1;
+                }
+            }
+            Section::Bonds => {
+                if let (Some(ai), Some(aj)) = (parse_index(parts.first()), parse_index(parts.get(1))) {
+                    if let Some(mol) = current.as_ref().and_then(|name| molecules.get_mut(name)) {
+                        mol.bonds.push((ai.min(aj), ai.max(aj)));
+                    }
+                }
+            }
+            Section::Angles => {
+                if let (Some(ai), Some(aj), Some(ak)) = (
+                    parse_index(parts.first()),
+                    parse_index(parts.get(1)),
+                    parse_index(parts.get(2)),
+                ) {
+                    if let Some(mol) = current.as_ref().and_then(|name| molecules.get_mut(name)) {
+                        mol.angles.push((ai, aj, ak));
+                    }
+                }
+            }
+            Section::Dihedrals => {
+                if let (Some(ai), Some(aj), Some(ak), Some(al)) = (
+                    parse_index(parts.first()),
+                    parse_index(parts.get(1)),
+                    parse_index(parts.get(2)),
+                    parse_index(parts.get(3)),
+                ) {
+                    if let Some(mol) = current.as_ref().and_then(|name| molecules.get_mut(name)) {
+                        mol.dihedrals.push((ai, aj, ak, al));
+                    }
+                }
+            }
+            Section::Molecules => {
+                if let Some(&name) = parts.first() {
+                    let count: usize = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(1);
+                    instances.push((name.to_string(), count));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut topology = Topology {
+        bonds: Vec::new(),
+        angles: Vec::new(),
+        dihedrals: Vec::new(),
+    };
+    let mut offset: u32 = 0;
+
+    for (name, count) in &instances {
+        let Some(mol) = molecules.get(name) else {
+            continue;
+        };
+        for _ in 0..*count {
+            for &(a, b) in &mol.bonds {
+                topology.bonds.push((a + offset, b + offset));
+            }
+            for &(a, b, c) in &mol.angles {
+                topology.angles.push((a + offset, b + offset, c + offset));
+            }
+            for &(a, b, c, d) in &mol.dihedrals {
+                topology.dihedrals.push((a + offset, b + offset, c + offset, d + offset));
+            }
+            offset += mol.n_atoms as u32;
+        }
+    }
+
+    Ok(topology)
+}
+
+/// Parse a 1-indexed GROMACS atom reference into a 0-indexed one.
+fn parse_index(token: Option<&&str>) -> Option<u32> {
+    let n: u32 = token?.parse().ok()?;
+    n.checked_sub(1)
+}
+
+/// Run a minimal C-style preprocessor over `.top`/`.itp` text: resolve
+/// `#include "file"` against `include_dirs` (recursively), record
+/// `#define NAME` as a flag (no macro substitution), and drop lines inside
+/// a false `#ifdef`/`#ifndef`/`#else` branch.
+fn preprocess(
+    text: &str,
+    include_dirs: &[String],
+    defines: &mut std::collections::HashSet<String>,
+) -> Result<String, String> {
+    let mut out = String::new();
+    let mut cond_stack: Vec<bool> = Vec::new();
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+
+        if let Some(name) = trimmed.strip_prefix("#ifdef") {
+            cond_stack.push(defines.contains(name.trim()));
+            continue;
+        }
+        if let Some(name) = trimmed.strip_prefix("#ifndef") {
+            cond_stack.push(!defines.contains(name.trim()));
+            continue;
+        }
+        if trimmed == "#else" {
+            if let Some(top) = cond_stack.last_mut() {
+                *top = !*top;
+            }
+            continue;
+        }
+        if trimmed == "#endif" {
+            cond_stack.pop();
+            continue;
+        }
+
+        if cond_stack.iter().any(|&active| !active) {
+            continue;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("#define") {
+            if let Some(name) = rest.trim().split_whitespace().next() {
+                defines.insert(name.to_string());
+            }
+            continue;
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("#include") {
+            let fname = rest.trim().trim_matches(|c| c == '"' || c == '<' || c == '>');
+            let path = resolve_include(fname, include_dirs)?;
+            let included_text = std::fs::read_to_string(&path)
+                .map_err(|e| format!("failed to read include '{}': {}", path, e))?;
+            out.push_str(&preprocess(&included_text, include_dirs, defines)?);
+            out.push('\n');
+            continue;
+        }
+
+        out.push_str(line);
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+/// Resolve an `#include` target against `include_dirs` (in order), falling
+/// back to treating it as relative to the current directory.
+fn resolve_include(fname: &str, include_dirs: &[String]) -> Result<String, String> {
+    for dir in include_dirs {
+        let candidate = format!("{}/{}", dir.trim_end_matches('/'), fname);
+        if std::path::Path::new(&candidate).is_file() {
+            return Ok(candidate);
+        }
+    }
+    if std::path::Path::new(fname).is_file() {
+        return Ok(fname.to_string());
+    }
+    Err(format!(
+        "cannot resolve #include \"{}\" in {:?}",
+        fname, include_dirs
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -101,4 +374,55 @@ protein  3
         assert_eq!(bonds.len(), 1);
         assert_eq!(bonds[0], (0, 1));
     }
+
+    #[test]
+    fn test_parse_top_molecules_expansion() {
+        let text = r#"
+[ moleculetype ]
+water  2
+
+[ atoms ]
+     1  OW   1  SOL  OW  1  0.0  16.0
+     2  HW1  1  SOL  HW1 1  0.0   1.0
+     3  HW2  1  SOL  HW2 1  0.0   1.0
+
+[ bonds ]
+     1     2     1
+     1     3     1
+
+[ molecules ]
+water  2
+"#;
+        let topology = parse_top(text, &[]).unwrap();
+        assert_eq!(topology.bonds.len(), 4);
+        assert_eq!(topology.bonds[0], (0, 1));
+        assert_eq!(topology.bonds[1], (0, 2));
+        assert_eq!(topology.bonds[2], (3, 4));
+        assert_eq!(topology.bonds[3], (3, 5));
+    }
+
+    #[test]
+    fn test_parse_top_ifdef_skips_block() {
+        let text = r#"
+[ moleculetype ]
+mol  3
+
+[ atoms ]
+     1  A  1  MOL  A  1  0.0  1.0
+     2  B  1  MOL  B  1  0.0  1.0
+
+[ bonds ]
+     1     2     1
+#ifdef FLEXIBLE
+[ angles ]
+     1     2     1     1
+#endif
+
+[ molecules ]
+mol  1
+"#;
+        let topology = parse_top(text, &[]).unwrap();
+        assert_eq!(topology.bonds.len(), 1);
+        assert_eq!(topology.angles.len(), 0);
+    }
 }
@@ -0,0 +1,182 @@
+/// Serialize a [`ParsedStructure`] back to text, the inverse of
+/// [`crate::parser`]/[`crate::gro`]/[`crate::xyz`]. Since `ParsedStructure`
+/// only carries atomic numbers (not atom/residue names), writers fall back
+/// to the element symbol as the atom name and a generic residue.
+
+use crate::parser::{atomic_num_to_symbol, matrix_to_cell_params, ParsedStructure};
+
+/// Write a structure as a minimal PDB: `ATOM` records (positions only, one
+/// model) followed by `CONECT` records for every bond, and a leading
+/// `CRYST1` record when `box_matrix` is present.
+pub fn write_pdb(structure: &ParsedStructure) -> String {
+    let mut out = String::new();
+
+    if let Some(box_matrix) = structure.box_matrix {
+        let (a, b, c, alpha, beta, gamma) = matrix_to_cell_params(&box_matrix);
+        out.push_str(&format!(
+            "CRYST1{:9.3}{:9.3}{:9.3}{:7.2}{:7.2}{:7.2} P 1           1\n",
+            a, b, c, alpha, beta, gamma
+        ));
+    }
+
+    for i in 0..structure.n_atoms {
+        let symbol = atomic_num_to_symbol(structure.elements[i]);
+        let x = structure.positions[i * 3];
+        let y = structure.positions[i * 3 + 1];
+        let z = structure.positions[i * 3 + 2];
+        let serial = i + 1;
+        out.push_str(&format!(
+            "ATOM  {:5} {:<4} UNK A{:4}    {:8.3}{:8.3}{:8.3}{:6.2}{:6.2}          {:>2}\n",
+            serial, symbol, 1, x, y, z, 1.00, 0.00, symbol
+        ));
+    }
+
+    for i in 0..structure.n_atoms {
+        let mut partners: Vec<usize> = Vec::new();
+        for &(a, b) in &structure.bonds {
+            if a as usize == i {
+                partners.push(b as usize);
+            } else if b as usize == i {
+                partners.push(a as usize);
+            }
+        }
+        if partners.is_empty() {
+            continue;
+        }
+        partners.sort_unstable();
+        for chunk in partners.chunks(4) {
+            out.push_str(&format!("CONECT{:5}", i + 1));
+            for &p in chunk {
+                out.push_str(&format!("{:5}", p + 1));
+            }
+            out.push('\n');
+        }
+    }
+
+    out.push_str("END\n");
+    out
+}
+
+/// Write a structure as a GRO file: fixed-width atom records in nm (Å ÷
+/// 10), a trailing box line, and velocities when present.
+pub fn write_gro(structure: &ParsedStructure) -> String {
+    let mut out = String::new();
+    out.push_str("Generated by megane\n");
+    out.push_str(&format!("{:5}\n", structure.n_atoms));
+
+    for i in 0..structure.n_atoms {
+        let symbol = atomic_num_to_symbol(structure.elements[i]);
+        let x = structure.positions[i * 3] / 10.0;
+        let y = structure.positions[i * 3 + 1] / 10.0;
+        let z = structure.positions[i * 3 + 2] / 10.0;
+        out.push_str(&format!(
+            "{:5}{:<5}{:>5}{:5}{:8.3}{:8.3}{:8.3}",
+            1, "UNK", symbol, i + 1, x, y, z
+        ));
+        if let Some(velocities) = &structure.velocities {
+            let vx = velocities[i * 3] / 10.0;
+            let vy = velocities[i * 3 + 1] / 10.0;
+            let vz = velocities[i * 3 + 2] / 10.0;
+            out.push_str(&format!("{:8.4}{:8.4}{:8.4}", vx, vy, vz));
+        }
+        out.push('\n');
+    }
+
+    match structure.box_matrix {
+        Some(m) => {
+            out.push_str(&format!(
+                "{:10.5}{:10.5}{:10.5}{:10.5}{:10.5}{:10.5}{:10.5}{:10.5}{:10.5}\n",
+                m[0] / 10.0,
+                m[4] / 10.0,
+                m[8] / 10.0,
+                m[1] / 10.0,
+                m[2] / 10.0,
+                m[3] / 10.0,
+                m[5] / 10.0,
+                m[6] / 10.0,
+                m[7] / 10.0
+            ));
+        }
+        None => out.push_str("   0.00000   0.00000   0.00000\n"),
+    }
+
+    out
+}
+
+/// Write a structure as a plain XYZ file: atom count, comment line, then
+/// `element x y z` rows (Angstrom).
+pub fn write_xyz(structure: &ParsedStructure) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("{}\n", structure.n_atoms));
+    out.push_str("Generated by megane\n");
+
+    for i in 0..structure.n_atoms {
+        let symbol = atomic_num_to_symbol(structure.elements[i]);
+        let x = structure.positions[i * 3];
+        let y = structure.positions[i * 3 + 1];
+        let z = structure.positions[i * 3 + 2];
+        out.push_str(&format!("{} {:.6} {:.6} {:.6}\n", symbol, x, y, z));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_atom_structure() -> ParsedStructure {
+        ParsedStructure {
+            n_atoms: 2,
+            positions: vec![0.0, 0.0, 0.0, 1.5, 0.0, 0.0],
+            elements: vec![6, 8],
+            bonds: vec![(0, 1)],
+            n_file_bonds: 1,
+            bond_orders: None,
+            box_matrix: None,
+            frame_positions: Vec::new(),
+            formal_charges: vec![0, 0],
+            space_group: None,
+            velocities: None,
+        }
+    }
+
+    #[test]
+    fn write_gro_then_parse_round_trips_positions_and_elements() {
+        let structure = two_atom_structure();
+        let text = write_gro(&structure);
+        let reparsed = crate::gro::parse(&text).expect("written GRO should reparse");
+
+        assert_eq!(reparsed.n_atoms, structure.n_atoms);
+        assert_eq!(reparsed.elements, structure.elements);
+        for (a, b) in reparsed.positions.iter().zip(&structure.positions) {
+            assert!((a - b).abs() < 1e-3, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn write_xyz_then_parse_round_trips_positions_and_elements() {
+        let structure = two_atom_structure();
+        let text = write_xyz(&structure);
+        let reparsed = crate::xyz::parse(&text).expect("written XYZ should reparse");
+
+        assert_eq!(reparsed.n_atoms, structure.n_atoms);
+        assert_eq!(reparsed.elements, structure.elements);
+        for (a, b) in reparsed.positions.iter().zip(&structure.positions) {
+            assert!((a - b).abs() < 1e-4, "{} vs {}", a, b);
+        }
+    }
+
+    #[test]
+    fn write_pdb_then_parse_round_trips_positions_and_elements() {
+        let structure = two_atom_structure();
+        let text = write_pdb(&structure);
+        let reparsed = crate::parser::parse(&text).expect("written PDB should reparse");
+
+        assert_eq!(reparsed.n_atoms, structure.n_atoms);
+        assert_eq!(reparsed.elements, structure.elements);
+        for (a, b) in reparsed.positions.iter().zip(&structure.positions) {
+            assert!((a - b).abs() < 1e-2, "{} vs {}", a, b);
+        }
+    }
+}
@@ -4,11 +4,42 @@
 ///   Line 1: number of atoms
 ///   Line 2: comment
 ///   Lines 3..n+2: element x y z (Angstrom)
+///
+/// Also understands the Extended XYZ (extxyz) convention, where the comment
+/// line carries `key=value` pairs: `Lattice="ax ay az bx by bz cx cy cz"`
+/// gives the 3x3 cell, and `Properties=species:S:1:pos:R:3:...` declares the
+/// columns of each atom line so extra per-atom fields (charges, forces, ...)
+/// can be read positionally instead of assuming the fixed `element x y z`
+/// layout.
 
 use std::collections::HashSet;
 use crate::bonds;
 use crate::parser::symbol_to_atomic_num;
 
+/// One `name:type:count` entry from an extxyz `Properties` string.
+struct PropertyField {
+    name: String,
+    count: usize,
+}
+
+/// Column layout for an atom line, derived from `Properties=...`.
+struct ColumnLayout {
+    /// Index of the first token holding the element symbol.
+    species_col: usize,
+    /// Index of the first token holding x (y, z follow immediately).
+    pos_col: usize,
+}
+
+impl ColumnLayout {
+    /// The plain XYZ layout: `element x y z`.
+    fn default_layout() -> Self {
+        ColumnLayout {
+            species_col: 0,
+            pos_col: 1,
+        }
+    }
+}
+
 pub fn parse(text: &str) -> Result<crate::parser::ParsedStructure, String> {
     let lines: Vec<&str> = text.lines().collect();
     if lines.len() < 3 {
@@ -19,6 +50,7 @@ pub fn parse(text: &str) -> Result<crate::parser::ParsedStructure, String> {
     let mut first_positions: Option<Vec<f32>> = None;
     let mut first_elements: Option<Vec<u8>> = None;
     let mut first_n_atoms = 0usize;
+    let mut box_matrix: Option<[f32; 9]> = None;
     let mut frame_positions: Vec<Vec<f32>> = Vec::new();
 
     while offset < lines.len() {
@@ -36,31 +68,37 @@ pub fn parse(text: &str) -> Result<crate::parser::ParsedStructure, String> {
             break; // incomplete frame, skip
         }
 
-        // Line 2: comment (skip)
+        // Line 2: comment, possibly carrying extxyz key=value pairs
+        let comment = lines[offset + 1];
+        let props = parse_comment_line(comment);
         offset += 2;
 
+        let layout = match props.get("Properties") {
+            Some(spec) => parse_properties(spec)?,
+            None => ColumnLayout::default_layout(),
+        };
+
         let mut positions = Vec::with_capacity(n_atoms * 3);
         let mut elements = Vec::with_capacity(n_atoms);
 
         for i in 0..n_atoms {
             let line = lines[offset + i];
             let parts: Vec<&str> = line.split_whitespace().collect();
-            if parts.len() < 4 {
+            let min_cols = layout.pos_col + 3;
+            if parts.len() < min_cols.max(layout.species_col + 1) {
                 return Err(format!("XYZ atom line {} too short", offset + i + 1));
             }
 
-            // Element symbol
-            let sym = crate::parser::capitalize(parts[0]);
+            let sym = capitalize_symbol(parts[layout.species_col]);
             elements.push(symbol_to_atomic_num(&sym));
 
-            // Coordinates (already in Angstrom)
-            let x: f32 = parts[1]
+            let x: f32 = parts[layout.pos_col]
                 .parse()
                 .map_err(|_| format!("bad x coord at line {}", offset + i + 1))?;
-            let y: f32 = parts[2]
+            let y: f32 = parts[layout.pos_col + 1]
                 .parse()
                 .map_err(|_| format!("bad y coord at line {}", offset + i + 1))?;
-            let z: f32 = parts[3]
+            let z: f32 = parts[layout.pos_col + 2]
                 .parse()
                 .map_err(|_| format!("bad z coord at line {}", offset + i + 1))?;
 
@@ -75,6 +113,9 @@ pub fn parse(text: &str) -> Result<crate::parser::ParsedStructure, String> {
             first_n_atoms = n_atoms;
             first_positions = Some(positions);
             first_elements = Some(elements);
+            if let Some(lattice) = props.get("Lattice") {
+                box_matrix = parse_lattice(lattice);
+            }
         } else if n_atoms == first_n_atoms {
             frame_positions.push(positions);
         }
@@ -85,7 +126,10 @@ pub fn parse(text: &str) -> Result<crate::parser::ParsedStructure, String> {
 
     // Infer bonds from first frame
     let empty_bonds = HashSet::new();
-    let bonds = bonds::infer_bonds(&positions, &elements, first_n_atoms, &empty_bonds);
+    let bonds = match &box_matrix {
+        Some(box_matrix) => bonds::infer_bonds_pbc(&positions, &elements, first_n_atoms, box_matrix),
+        None => bonds::infer_bonds(&positions, &elements, first_n_atoms, &empty_bonds),
+    };
 
     Ok(crate::parser::ParsedStructure {
         n_atoms: first_n_atoms,
@@ -94,8 +138,117 @@ pub fn parse(text: &str) -> Result<crate::parser::ParsedStructure, String> {
         bonds,
         n_file_bonds: 0,
         bond_orders: None,
-        box_matrix: None,
+        box_matrix,
         frame_positions,
+        formal_charges: vec![0; first_n_atoms],
+        space_group: None,
+        velocities: None,
+    })
+}
+
+/// Parse a comment line into `key=value` pairs, honoring the extxyz
+/// convention where a value may be double-quoted to protect embedded
+/// whitespace (e.g. `Lattice="1.0 0.0 0.0 ..."`).
+fn parse_comment_line(line: &str) -> std::collections::HashMap<String, String> {
+    let mut map = std::collections::HashMap::new();
+    for token in tokenize_preserving_quotes(line) {
+        if let Some(eq) = token.find('=') {
+            let key = token[..eq].to_string();
+            let value = token[eq + 1..].trim_matches('"').to_string();
+            map.insert(key, value);
+        }
+    }
+    map
+}
+
+/// Split on whitespace, but keep whitespace inside double quotes as part of
+/// a single token (so `Lattice="1 0 0 0 1 0 0 0 1"` survives as one token).
+fn tokenize_preserving_quotes(line: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(c);
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Parse a `Lattice="ax ay az bx by bz cx cy cz"` value into a row-major 3x3
+/// matrix (each row is one lattice vector).
+fn parse_lattice(value: &str) -> Option<[f32; 9]> {
+    let vals: Vec<f32> = value
+        .split_whitespace()
+        .filter_map(|s| s.parse().ok())
+        .collect();
+    if vals.len() != 9 {
+        return None;
+    }
+    let mut m = [0.0f32; 9];
+    m.copy_from_slice(&vals);
+    Some(m)
+}
+
+/// Parse a `Properties=species:S:1:pos:R:3:...` schema into a column layout.
+fn parse_properties(spec: &str) -> Result<ColumnLayout, String> {
+    let parts: Vec<&str> = spec.split(':').collect();
+    if parts.len() % 3 != 0 {
+        return Err(format!("malformed Properties spec '{}'", spec));
+    }
+
+    let mut fields = Vec::new();
+    for chunk in parts.chunks(3) {
+        let count: usize = chunk[2]
+            .parse()
+            .map_err(|_| format!("bad column count in Properties field '{}'", chunk[0]))?;
+        fields.push(PropertyField {
+            name: chunk[0].to_string(),
+            count,
+        });
+    }
+
+    let mut species_col = None;
+    let mut pos_col = None;
+    let mut col = 0usize;
+    for field in &fields {
+        if field.name == "species" {
+            species_col = Some(col);
+        } else if field.name == "pos" {
+            pos_col = Some(col);
+        }
+        col += field.count;
+    }
+
+    Ok(ColumnLayout {
+        species_col: species_col.ok_or("Properties spec has no 'species' field")?,
+        pos_col: pos_col.ok_or("Properties spec has no 'pos' field")?,
     })
 }
 
+/// Capitalize element symbol: first char upper, rest lower.
+fn capitalize_symbol(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(c) => {
+            let upper: String = c.to_uppercase().collect();
+            let lower: String = chars.flat_map(|c| c.to_lowercase()).collect();
+            format!("{}{}", upper, lower)
+        }
+    }
+}
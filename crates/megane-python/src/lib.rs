@@ -1,9 +1,34 @@
+use std::collections::HashMap;
+
 use megane_core::parser::ParsedStructure;
-use numpy::ndarray::{Array1, Array2};
-use numpy::{IntoPyArray, PyArray1, PyArray2};
+use numpy::ndarray::{Array1, Array2, Array3};
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyArray3, PyArrayMethods};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 
+/// Memory-map `path` and hand its bytes to `parse` as `&str`, so large
+/// files are parsed in place rather than first being copied into a Python
+/// `str`. `py.allow_threads` releases the GIL for the mmap + parse work,
+/// since none of it touches Python objects.
+fn parse_mapped<T>(
+    py: Python<'_>,
+    path: &str,
+    parse: impl FnOnce(&str) -> Result<T, String>,
+) -> PyResult<T> {
+    py.allow_threads(|| {
+        let file = std::fs::File::open(path)
+            .map_err(|e| PyValueError::new_err(format!("cannot open '{}': {}", path, e)))?;
+        // Safety: the file is not expected to be mutated by another process
+        // while mapped; if it is, reads may observe torn data rather than
+        // panicking (the standard caveat for file-backed mmap).
+        let mmap = unsafe { memmap2::Mmap::map(&file) }
+            .map_err(|e| PyValueError::new_err(format!("cannot mmap '{}': {}", path, e)))?;
+        let text = std::str::from_utf8(&mmap)
+            .map_err(|e| PyValueError::new_err(format!("'{}' is not valid UTF-8: {}", path, e)))?;
+        parse(text).map_err(PyValueError::new_err)
+    })
+}
+
 #[pyclass]
 struct PyStructure {
     #[pyo3(get)]
@@ -17,7 +42,20 @@ struct PyStructure {
     #[pyo3(get)]
     bond_orders: Py<PyArray1<u8>>,
     #[pyo3(get)]
+    formal_charges: Py<PyArray1<i8>>,
+    #[pyo3(get)]
     box_matrix: Py<PyArray2<f32>>,
+    /// Trajectory frames, shape `(n_frames, n_atoms, 3)`. `positions`
+    /// continues to hold the first frame's coordinates for backward
+    /// compatibility, as a separate array rather than a view into `frames`.
+    #[pyo3(get)]
+    frames: Py<PyArray3<f32>>,
+    #[pyo3(get)]
+    n_frames: usize,
+    /// Per-atom velocities (Angstrom/ps), shape `(n_atoms, 3)`. Empty
+    /// `(0, 3)` when the source format didn't record velocities.
+    #[pyo3(get)]
+    velocities: Py<PyArray2<f32>>,
 }
 
 impl PyStructure {
@@ -25,6 +63,15 @@ impl PyStructure {
         let n = data.n_atoms;
         let n_bonds = data.bonds.len();
 
+        let n_frames = 1 + data.frame_positions.len();
+        let mut frames_flat: Vec<f32> = Vec::with_capacity(n_frames * n * 3);
+        frames_flat.extend_from_slice(&data.positions);
+        for frame in &data.frame_positions {
+            frames_flat.extend_from_slice(frame);
+        }
+        let frames_array =
+            Array3::from_shape_vec((n_frames, n, 3), frames_flat).expect("frames reshape");
+
         let pos_array = Array2::from_shape_vec((n, 3), data.positions)
             .expect("positions reshape");
 
@@ -44,19 +91,144 @@ impl PyStructure {
         let bo_vec = data.bond_orders.unwrap_or_else(|| vec![1u8; n_bonds]);
         let bo_array = Array1::from_vec(bo_vec);
 
+        let charge_array = Array1::from_vec(data.formal_charges);
+
         let box_vec = match data.box_matrix {
             Some(m) => m.to_vec(),
             None => vec![0.0f32; 9],
         };
         let box_array = Array2::from_shape_vec((3, 3), box_vec).expect("box reshape");
 
+        let vel_array = match data.velocities {
+            Some(v) => Array2::from_shape_vec((n, 3), v).expect("velocities reshape"),
+            None => Array2::from_shape_vec((0, 3), vec![]).expect("empty velocities"),
+        };
+
         Self {
             n_atoms: n,
             positions: pos_array.into_pyarray(py).into(),
             elements: elem_array.into_pyarray(py).into(),
             bonds: bond_array.into_pyarray(py).into(),
             bond_orders: bo_array.into_pyarray(py).into(),
+            formal_charges: charge_array.into_pyarray(py).into(),
             box_matrix: box_array.into_pyarray(py).into(),
+            frames: frames_array.into_pyarray(py).into(),
+            n_frames,
+            velocities: vel_array.into_pyarray(py).into(),
+        }
+    }
+
+    /// Rebuild a core `ParsedStructure` from this structure's numpy arrays,
+    /// the inverse of [`PyStructure::from_parsed`]. `bond_orders`,
+    /// `box_matrix` (all-zero), and `velocities` (shape `(0, 3)`) are
+    /// treated as absent. `frame_positions` is rebuilt from `frames`
+    /// (skipping index 0, whose coordinates are taken from `positions`), and
+    /// `space_group` is always dropped since `PyStructure` doesn't carry it.
+    fn to_parsed(&self, py: Python<'_>) -> ParsedStructure {
+        let positions: Vec<f32> = self
+            .positions
+            .bind(py)
+            .readonly()
+            .as_array()
+            .iter()
+            .copied()
+            .collect();
+        let n_atoms = self.n_atoms;
+        let elements: Vec<u8> = self
+            .elements
+            .bind(py)
+            .readonly()
+            .as_array()
+            .iter()
+            .copied()
+            .collect();
+
+        let bonds_flat: Vec<u32> = self
+            .bonds
+            .bind(py)
+            .readonly()
+            .as_array()
+            .iter()
+            .copied()
+            .collect();
+        let bonds: Vec<(u32, u32)> = bonds_flat.chunks(2).map(|c| (c[0], c[1])).collect();
+        let n_file_bonds = bonds.len();
+
+        let bond_orders: Vec<u8> = self
+            .bond_orders
+            .bind(py)
+            .readonly()
+            .as_array()
+            .iter()
+            .copied()
+            .collect();
+        let bond_orders = if bond_orders.is_empty() {
+            None
+        } else {
+            Some(bond_orders)
+        };
+
+        let box_flat: Vec<f32> = self
+            .box_matrix
+            .bind(py)
+            .readonly()
+            .as_array()
+            .iter()
+            .copied()
+            .collect();
+        let box_matrix = if box_flat.iter().all(|&v| v == 0.0) {
+            None
+        } else {
+            let mut m = [0.0f32; 9];
+            m.copy_from_slice(&box_flat);
+            Some(m)
+        };
+
+        let velocities: Vec<f32> = self
+            .velocities
+            .bind(py)
+            .readonly()
+            .as_array()
+            .iter()
+            .copied()
+            .collect();
+        let velocities = if velocities.is_empty() {
+            None
+        } else {
+            Some(velocities)
+        };
+
+        let formal_charges: Vec<i8> = self
+            .formal_charges
+            .bind(py)
+            .readonly()
+            .as_array()
+            .iter()
+            .copied()
+            .collect();
+
+        let frame_positions: Vec<Vec<f32>> = self
+            .frames
+            .bind(py)
+            .readonly()
+            .as_array()
+            .outer_iter()
+            .skip(1)
+            .map(|frame| frame.iter().copied().collect())
+            .collect();
+
+        ParsedStructure {
+            n_atoms,
+            positions,
+            elements,
+            bonds,
+            n_file_bonds,
+            bond_orders,
+            box_matrix,
+            frame_positions,
+            formal_charges,
+            space_group: None,
+            velocities,
         }
     }
 }
@@ -89,11 +261,172 @@ fn parse_mol(py: Python<'_>, text: &str) -> PyResult<PyStructure> {
     Ok(PyStructure::from_parsed(py, data))
 }
 
+/// Parse a multi-record SDF file text. Returns one `(structure, data
+/// fields)` pair per `$$$$`-terminated record, with `data fields` holding
+/// the `> <FieldName>` tag/value pairs that followed the molblock.
+#[pyfunction]
+fn parse_sdf(py: Python<'_>, text: &str) -> PyResult<Vec<(PyStructure, HashMap<String, String>)>> {
+    let records = megane_core::mol::parse_sdf(text).map_err(|e| PyValueError::new_err(e))?;
+    Ok(records
+        .into_iter()
+        .map(|(data, fields)| (PyStructure::from_parsed(py, data), fields))
+        .collect())
+}
+
+/// Parse an mmCIF/PDBx file text and return structured data.
+#[pyfunction]
+fn parse_mmcif(py: Python<'_>, text: &str) -> PyResult<PyStructure> {
+    let data = megane_core::mmcif::parse_mmcif(text).map_err(|e| PyValueError::new_err(e))?;
+    Ok(PyStructure::from_parsed(py, data))
+}
+
+/// Parse a PDB file at `path` via memory mapping, avoiding a Python-side read.
+#[pyfunction]
+fn parse_pdb_file(py: Python<'_>, path: &str) -> PyResult<PyStructure> {
+    let data = parse_mapped(py, path, megane_core::parser::parse)?;
+    Ok(PyStructure::from_parsed(py, data))
+}
+
+/// Parse a GRO file at `path` via memory mapping, avoiding a Python-side read.
+#[pyfunction]
+fn parse_gro_file(py: Python<'_>, path: &str) -> PyResult<PyStructure> {
+    let data = parse_mapped(py, path, megane_core::gro::parse)?;
+    Ok(PyStructure::from_parsed(py, data))
+}
+
+/// Parse an XYZ file at `path` via memory mapping, avoiding a Python-side read.
+#[pyfunction]
+fn parse_xyz_file(py: Python<'_>, path: &str) -> PyResult<PyStructure> {
+    let data = parse_mapped(py, path, megane_core::xyz::parse)?;
+    Ok(PyStructure::from_parsed(py, data))
+}
+
+/// Parse a multi-record SDF file at `path` via memory mapping, avoiding a
+/// Python-side read.
+#[pyfunction]
+fn parse_sdf_file(py: Python<'_>, path: &str) -> PyResult<Vec<(PyStructure, HashMap<String, String>)>> {
+    let records = parse_mapped(py, path, megane_core::mol::parse_sdf)?;
+    Ok(records
+        .into_iter()
+        .map(|(data, fields)| (PyStructure::from_parsed(py, data), fields))
+        .collect())
+}
+
+/// Parse an mmCIF/PDBx file at `path` via memory mapping, avoiding a Python-side read.
+#[pyfunction]
+fn parse_mmcif_file(py: Python<'_>, path: &str) -> PyResult<PyStructure> {
+    let data = parse_mapped(py, path, megane_core::mmcif::parse_mmcif)?;
+    Ok(PyStructure::from_parsed(py, data))
+}
+
+/// Parse an MDL Molfile at `path` via memory mapping, avoiding a Python-side read.
+#[pyfunction]
+fn parse_mol_file(py: Python<'_>, path: &str) -> PyResult<PyStructure> {
+    let data = parse_mapped(py, path, megane_core::mol::parse)?;
+    Ok(PyStructure::from_parsed(py, data))
+}
+
+/// Parse a GROMACS `.top` topology: `#include`/`#define`/`#ifdef` are
+/// preprocessed first (resolving includes against `include_dirs`), then
+/// bonds/angles/dihedrals are expanded per the file's `[ molecules ]`
+/// counts. Returns `(bonds, angles, dihedrals)` as lists of index tuples.
+#[pyfunction]
+#[pyo3(signature = (text, include_dirs=vec![]))]
+fn parse_top(
+    text: &str,
+    include_dirs: Vec<String>,
+) -> PyResult<(Vec<(u32, u32)>, Vec<(u32, u32, u32)>, Vec<(u32, u32, u32, u32)>)> {
+    let topology =
+        megane_core::top::parse_top(text, &include_dirs).map_err(|e| PyValueError::new_err(e))?;
+    Ok((topology.bonds, topology.angles, topology.dihedrals))
+}
+
+/// Group a structure's atoms into connected components (molecules) via its
+/// bond graph. Returns one list of atom indices per fragment.
+#[pyfunction]
+fn fragments(py: Python<'_>, structure: &PyStructure) -> Vec<Vec<u32>> {
+    megane_core::fragments::fragments(&structure.to_parsed(py))
+}
+
+/// Strip unwanted fragments (known counterions/solvent, and/or all but the
+/// `keep_largest` biggest remaining fragments) from a structure, returning
+/// a new `PyStructure` with atom indices remapped to be contiguous.
+#[pyfunction]
+#[pyo3(signature = (structure, remove_known_salts=true, keep_largest=None))]
+fn strip_salts(
+    py: Python<'_>,
+    structure: &PyStructure,
+    remove_known_salts: bool,
+    keep_largest: Option<usize>,
+) -> PyStructure {
+    let mut data = structure.to_parsed(py);
+    let opts = megane_core::fragments::StripOptions {
+        remove_known_salts,
+        keep_largest,
+    };
+    megane_core::fragments::strip_salts(&mut data, &opts);
+    PyStructure::from_parsed(py, data)
+}
+
+/// Expand a structure's asymmetric unit into a full unit cell (optionally
+/// tiled into a `supercell`), applying the operators for `space_group`.
+/// Raises `ValueError` if `space_group` isn't recognized or the structure
+/// has no `box_matrix`.
+#[pyfunction]
+#[pyo3(signature = (structure, space_group, supercell=(1, 1, 1), tolerance=0.1))]
+fn expand_unit_cell(
+    py: Python<'_>,
+    structure: &PyStructure,
+    space_group: &str,
+    supercell: (u32, u32, u32),
+    tolerance: f32,
+) -> PyResult<PyStructure> {
+    let ops = megane_core::symmetry::lookup(space_group)
+        .ok_or_else(|| PyValueError::new_err(format!("unrecognized space group '{}'", space_group)))?;
+    let mut data = structure.to_parsed(py);
+    megane_core::symmetry::expand_unit_cell(&mut data, &ops, supercell, tolerance)
+        .map_err(PyValueError::new_err)?;
+    Ok(PyStructure::from_parsed(py, data))
+}
+
+/// Serialize a `PyStructure` back to PDB text (`ATOM`/`CONECT` records).
+#[pyfunction]
+fn write_pdb(py: Python<'_>, structure: &PyStructure) -> String {
+    megane_core::writer::write_pdb(&structure.to_parsed(py))
+}
+
+/// Serialize a `PyStructure` back to GRO text (nm units, optional velocities).
+#[pyfunction]
+fn write_gro(py: Python<'_>, structure: &PyStructure) -> String {
+    megane_core::writer::write_gro(&structure.to_parsed(py))
+}
+
+/// Serialize a `PyStructure` back to plain XYZ text.
+#[pyfunction]
+fn write_xyz(py: Python<'_>, structure: &PyStructure) -> String {
+    megane_core::writer::write_xyz(&structure.to_parsed(py))
+}
+
 #[pymodule]
 fn megane_parser(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(parse_pdb, m)?)?;
     m.add_function(wrap_pyfunction!(parse_gro, m)?)?;
     m.add_function(wrap_pyfunction!(parse_xyz, m)?)?;
     m.add_function(wrap_pyfunction!(parse_mol, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_sdf, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_mmcif, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_top, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_pdb_file, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_gro_file, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_xyz_file, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_mol_file, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_sdf_file, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_mmcif_file, m)?)?;
+    m.add_function(wrap_pyfunction!(fragments, m)?)?;
+    m.add_function(wrap_pyfunction!(strip_salts, m)?)?;
+    m.add_function(wrap_pyfunction!(expand_unit_cell, m)?)?;
+    m.add_function(wrap_pyfunction!(write_pdb, m)?)?;
+    m.add_function(wrap_pyfunction!(write_gro, m)?)?;
+    m.add_function(wrap_pyfunction!(write_xyz, m)?)?;
     Ok(())
 }